@@ -0,0 +1,7 @@
+pub mod origins;
+pub mod url_endpoints;
+pub mod usage;
+
+pub use origins::{Origin, OriginConfig, Origins};
+pub use url_endpoints::{UrlEndpoint, UrlEndpointConfig, UrlEndpoints, UrlRewriter};
+pub use usage::Usage;
@@ -0,0 +1,347 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{check_success, parse_success};
+use crate::http::{segment, HttpRequest, Method};
+use crate::metrics::Operation;
+use crate::types::{expose_secret, Secret};
+use crate::ImageKit;
+
+/// An origin registered in the account.
+///
+/// Refer: https://docs.imagekit.io/api-reference/account-management-api#origin-apis
+#[derive(Debug, Deserialize)]
+pub struct Origin {
+    /// Unique identifier of the origin
+    pub id: String,
+    #[serde(flatten)]
+    pub config: OriginConfig,
+}
+
+/// Configuration of an origin, tagged by its `type`.
+///
+/// Secrets are sent when creating or updating an origin but are never
+/// returned by the API, so they are `None` on retrieved origins.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OriginConfig {
+    S3(S3Origin),
+    S3Compatible(S3CompatibleOrigin),
+    CloudinaryBackup(CloudinaryBackupOrigin),
+    WebFolder(WebFolderOrigin),
+    WebProxy(WebProxyOrigin),
+    Gcs(GcsOrigin),
+    AzureBlob(AzureBlobOrigin),
+    AkeneoPim(AkeneoPimOrigin),
+}
+
+/// Amazon S3 bucket.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Origin {
+    pub name: String,
+    pub bucket: String,
+    /// Path in the bucket used as the root of the origin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    pub access_key: String,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub secret_key: Option<Secret>,
+    /// Whether to send a `Canonical` header with the URL of the file
+    #[serde(default)]
+    pub include_canonical_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_for_canonical_header: Option<String>,
+}
+
+/// Storage compatible with the Amazon S3 API, e.g. MinIO or DigitalOcean
+/// Spaces.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct S3CompatibleOrigin {
+    pub name: String,
+    pub bucket: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    pub access_key: String,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub secret_key: Option<Secret>,
+    /// URL of the S3 compatible API
+    pub endpoint: String,
+    /// Whether to use path style URLs instead of virtual hosted URLs
+    #[serde(default)]
+    pub s3_force_path_style: bool,
+    #[serde(default)]
+    pub include_canonical_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_for_canonical_header: Option<String>,
+}
+
+/// Amazon S3 bucket used as a backup of a Cloudinary account.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudinaryBackupOrigin {
+    pub name: String,
+    pub bucket: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    pub access_key: String,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub secret_key: Option<Secret>,
+    #[serde(default)]
+    pub include_canonical_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_for_canonical_header: Option<String>,
+}
+
+/// Web server serving the files under `base_url`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebFolderOrigin {
+    pub name: String,
+    pub base_url: String,
+    /// Whether to forward the `Host` header of the request to the origin
+    #[serde(default)]
+    pub forward_host_header_to_origin: bool,
+    #[serde(default)]
+    pub include_canonical_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_for_canonical_header: Option<String>,
+}
+
+/// Any publicly accessible URL, fetched from the absolute URL in the path.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebProxyOrigin {
+    pub name: String,
+    #[serde(default)]
+    pub include_canonical_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_for_canonical_header: Option<String>,
+}
+
+/// Google Cloud Storage bucket.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GcsOrigin {
+    pub name: String,
+    pub bucket: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Email of the service account with access to the bucket
+    pub client_email: String,
+    /// Private key of the service account
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub private_key: Option<Secret>,
+    #[serde(default)]
+    pub include_canonical_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_for_canonical_header: Option<String>,
+}
+
+/// Azure Blob Storage container.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureBlobOrigin {
+    pub name: String,
+    pub container: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    pub account_name: String,
+    /// Shared access signature token with read access to the container
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub sas_token: Option<Secret>,
+    #[serde(default)]
+    pub include_canonical_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_for_canonical_header: Option<String>,
+}
+
+/// Akeneo PIM instance.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AkeneoPimOrigin {
+    pub name: String,
+    pub base_url: String,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub client_id: Option<Secret>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub client_secret: Option<Secret>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub username: Option<Secret>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "expose_secret"
+    )]
+    pub password: Option<Secret>,
+    #[serde(default)]
+    pub include_canonical_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_for_canonical_header: Option<String>,
+}
+
+#[async_trait]
+pub trait Origins {
+    /// Lists the origins of the account
+    async fn list_origins(&self) -> Result<Vec<Origin>>;
+
+    /// Retrieves the origin with the provided id
+    async fn get_origin<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<Origin>;
+
+    /// Creates an origin
+    async fn create_origin(&self, config: &OriginConfig) -> Result<Origin>;
+
+    /// Replaces the configuration of the origin with the provided id
+    async fn update_origin<T: AsRef<str> + Display + Send>(
+        &self,
+        id: T,
+        config: &OriginConfig,
+    ) -> Result<Origin>;
+
+    /// Deletes the origin with the provided id
+    async fn delete_origin<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<()>;
+}
+
+#[async_trait]
+impl Origins for ImageKit {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.list_origins", skip_all, err)
+    )]
+    async fn list_origins(&self) -> Result<Vec<Origin>> {
+        let response = self
+            .send(
+                Operation::ListOrigins,
+                HttpRequest::new(Method::Get, format!("{}/origins", self.accounts_endpoint())),
+            )
+            .await?;
+
+        parse_success(&response)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.get_origin", skip_all, fields(%id), err)
+    )]
+    async fn get_origin<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<Origin> {
+        let response = self
+            .send(
+                Operation::GetOrigin,
+                HttpRequest::new(
+                    Method::Get,
+                    format!(
+                        "{}/origins/{}",
+                        self.accounts_endpoint(),
+                        segment(id.as_ref())
+                    ),
+                ),
+            )
+            .await?;
+
+        parse_success(&response)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.create_origin", skip_all, err)
+    )]
+    async fn create_origin(&self, config: &OriginConfig) -> Result<Origin> {
+        let response = self
+            .send(
+                Operation::CreateOrigin,
+                HttpRequest::new(
+                    Method::Post,
+                    format!("{}/origins", self.accounts_endpoint()),
+                )
+                .json(config)?,
+            )
+            .await?;
+
+        parse_success(&response)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.update_origin", skip_all, fields(%id), err)
+    )]
+    async fn update_origin<T: AsRef<str> + Display + Send>(
+        &self,
+        id: T,
+        config: &OriginConfig,
+    ) -> Result<Origin> {
+        let response = self
+            .send(
+                Operation::UpdateOrigin,
+                HttpRequest::new(
+                    Method::Put,
+                    format!(
+                        "{}/origins/{}",
+                        self.accounts_endpoint(),
+                        segment(id.as_ref())
+                    ),
+                )
+                .json(config)?,
+            )
+            .await?;
+
+        parse_success(&response)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.delete_origin", skip_all, fields(%id), err)
+    )]
+    async fn delete_origin<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<()> {
+        let response = self
+            .send(
+                Operation::DeleteOrigin,
+                HttpRequest::new(
+                    Method::Delete,
+                    format!(
+                        "{}/origins/{}",
+                        self.accounts_endpoint(),
+                        segment(id.as_ref())
+                    ),
+                ),
+            )
+            .await?;
+
+        check_success(&response)
+    }
+}
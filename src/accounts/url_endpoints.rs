@@ -0,0 +1,185 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{check_success, parse_success};
+use crate::http::{segment, HttpRequest, Method};
+use crate::metrics::Operation;
+use crate::ImageKit;
+
+/// A URL endpoint registered in the account.
+///
+/// Refer: https://docs.imagekit.io/api-reference/account-management-api#url-endpoint-apis
+#[derive(Debug, Deserialize)]
+pub struct UrlEndpoint {
+    /// Unique identifier of the URL endpoint
+    pub id: String,
+    #[serde(flatten)]
+    pub config: UrlEndpointConfig,
+}
+
+/// Configuration of a URL endpoint.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlEndpointConfig {
+    pub description: String,
+    /// Path prefix of the URL endpoint, e.g. `products` for
+    /// `https://ik.imagekit.io/<id>/products`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_prefix: Option<String>,
+    /// Ids of the origins checked in order to find a file
+    #[serde(default)]
+    pub origins: Vec<String>,
+    /// Rewrites URLs of another service into ImageKit transformations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_rewriter: Option<UrlRewriter>,
+}
+
+/// URL rewriter of a URL endpoint, tagged by its `type`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UrlRewriter {
+    #[serde(rename_all = "camelCase")]
+    Cloudinary {
+        /// Whether to keep the delivery type, e.g. `upload`, in the path
+        #[serde(default)]
+        preserve_asset_delivery_types: bool,
+    },
+    Imgix,
+    Akamai,
+}
+
+#[async_trait]
+pub trait UrlEndpoints {
+    /// Lists the URL endpoints of the account
+    async fn list_url_endpoints(&self) -> Result<Vec<UrlEndpoint>>;
+
+    /// Retrieves the URL endpoint with the provided id
+    async fn get_url_endpoint<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<UrlEndpoint>;
+
+    /// Creates a URL endpoint
+    async fn create_url_endpoint(&self, config: &UrlEndpointConfig) -> Result<UrlEndpoint>;
+
+    /// Replaces the configuration of the URL endpoint with the provided id
+    async fn update_url_endpoint<T: AsRef<str> + Display + Send>(
+        &self,
+        id: T,
+        config: &UrlEndpointConfig,
+    ) -> Result<UrlEndpoint>;
+
+    /// Deletes the URL endpoint with the provided id
+    async fn delete_url_endpoint<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<()>;
+}
+
+#[async_trait]
+impl UrlEndpoints for ImageKit {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.list_url_endpoints", skip_all, err)
+    )]
+    async fn list_url_endpoints(&self) -> Result<Vec<UrlEndpoint>> {
+        let response = self
+            .send(
+                Operation::ListUrlEndpoints,
+                HttpRequest::new(
+                    Method::Get,
+                    format!("{}/url-endpoints", self.accounts_endpoint()),
+                ),
+            )
+            .await?;
+
+        parse_success(&response)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.get_url_endpoint", skip_all, fields(%id), err)
+    )]
+    async fn get_url_endpoint<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<UrlEndpoint> {
+        let response = self
+            .send(
+                Operation::GetUrlEndpoint,
+                HttpRequest::new(
+                    Method::Get,
+                    format!(
+                        "{}/url-endpoints/{}",
+                        self.accounts_endpoint(),
+                        segment(id.as_ref())
+                    ),
+                ),
+            )
+            .await?;
+
+        parse_success(&response)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.create_url_endpoint", skip_all, err)
+    )]
+    async fn create_url_endpoint(&self, config: &UrlEndpointConfig) -> Result<UrlEndpoint> {
+        let response = self
+            .send(
+                Operation::CreateUrlEndpoint,
+                HttpRequest::new(
+                    Method::Post,
+                    format!("{}/url-endpoints", self.accounts_endpoint()),
+                )
+                .json(config)?,
+            )
+            .await?;
+
+        parse_success(&response)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.update_url_endpoint", skip_all, fields(%id), err)
+    )]
+    async fn update_url_endpoint<T: AsRef<str> + Display + Send>(
+        &self,
+        id: T,
+        config: &UrlEndpointConfig,
+    ) -> Result<UrlEndpoint> {
+        let response = self
+            .send(
+                Operation::UpdateUrlEndpoint,
+                HttpRequest::new(
+                    Method::Put,
+                    format!(
+                        "{}/url-endpoints/{}",
+                        self.accounts_endpoint(),
+                        segment(id.as_ref())
+                    ),
+                )
+                .json(config)?,
+            )
+            .await?;
+
+        parse_success(&response)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "imagekit.delete_url_endpoint", skip_all, fields(%id), err)
+    )]
+    async fn delete_url_endpoint<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<()> {
+        let response = self
+            .send(
+                Operation::DeleteUrlEndpoint,
+                HttpRequest::new(
+                    Method::Delete,
+                    format!(
+                        "{}/url-endpoints/{}",
+                        self.accounts_endpoint(),
+                        segment(id.as_ref())
+                    ),
+                ),
+            )
+            .await?;
+
+        check_success(&response)
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+use serde::Deserialize;
+
+use crate::client::parse_success;
+use crate::http::{HttpRequest, Method};
+use crate::metrics::Operation;
+use crate::ImageKit;
+
+/// Maximum number of days between the start and end dates of a single
+/// usage request, as ImageKit requires ranges shorter than 90 days
+pub const MAX_USAGE_RANGE_DAYS: i64 = 89;
+
+/// Account usage between two dates, counters missing from the response are
+/// zero.
+///
+/// Refer: https://docs.imagekit.io/api-reference/account-management-api#get-usage
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AccountUsage {
+    /// Amount of bandwidth used in bytes
+    pub bandwidth_bytes: u64,
+    /// Storage used by the media library in bytes
+    pub media_library_storage_bytes: u64,
+    /// Number of video processing units used
+    pub video_processing_units_count: u64,
+    /// Number of extension units used
+    pub extension_units_count: u64,
+    /// Storage used by the cache of original files in bytes
+    pub original_cache_storage_bytes: u64,
+}
+
+impl AccountUsage {
+    /// Combines the usage of consecutive periods, bandwidth and units are
+    /// added while storage is taken from the latest period
+    pub(crate) fn merge(self, later: AccountUsage) -> AccountUsage {
+        AccountUsage {
+            bandwidth_bytes: self.bandwidth_bytes + later.bandwidth_bytes,
+            media_library_storage_bytes: later.media_library_storage_bytes,
+            video_processing_units_count: self.video_processing_units_count
+                + later.video_processing_units_count,
+            extension_units_count: self.extension_units_count + later.extension_units_count,
+            original_cache_storage_bytes: later.original_cache_storage_bytes,
+        }
+    }
+}
+
+#[async_trait]
+pub trait Usage {
+    /// Retrieves the account usage from `start_date` (inclusive) to
+    /// `end_date` (exclusive). Ranges longer than `MAX_USAGE_RANGE_DAYS` are
+    /// split into multiple requests and combined.
+    async fn get_usage(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<AccountUsage>;
+}
+
+#[async_trait]
+impl Usage for ImageKit {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "imagekit.get_usage",
+            skip_all,
+            fields(%start_date, %end_date),
+            err
+        )
+    )]
+    async fn get_usage(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<AccountUsage> {
+        let mut usage = AccountUsage::default();
+
+        for (start, end) in split_range(start_date, end_date)? {
+            let url = format!(
+                "{}/usage?startDate={}&endDate={}",
+                self.accounts_endpoint(),
+                start.format("%Y-%m-%d"),
+                end.format("%Y-%m-%d")
+            );
+            let response = self
+                .send(Operation::GetUsage, HttpRequest::new(Method::Get, url))
+                .await?;
+
+            usage = usage.merge(parse_success::<AccountUsage>(&response)?);
+        }
+
+        Ok(usage)
+    }
+}
+
+/// Splits the range into consecutive ranges of at most
+/// `MAX_USAGE_RANGE_DAYS` days
+pub(crate) fn split_range(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<(NaiveDate, NaiveDate)>> {
+    if start_date >= end_date {
+        bail!("Usage start date must be before the end date");
+    }
+
+    let mut ranges = Vec::new();
+    let mut start = start_date;
+
+    while start < end_date {
+        let end = (start + Duration::days(MAX_USAGE_RANGE_DAYS)).min(end_date);
+
+        ranges.push((start, end));
+        start = end;
+    }
+
+    Ok(ranges)
+}
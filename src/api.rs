@@ -0,0 +1,55 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::delete::Delete;
+use crate::file_details::Details;
+use crate::upload::types::Response;
+use crate::upload::{Options, Upload};
+
+/// Object-safe combination of `Upload`, `Delete` and `Details`, so clients
+/// can be stored as `Arc<dyn ImageKitApi>` and replaced with mocks. Methods
+/// have their own names so they never clash with the ones of the three
+/// traits when all of them are in scope.
+///
+/// Implemented for every type implementing the three traits, e.g.
+/// `ImageKit` and `FakeImageKit`:
+///
+/// ```ignore
+/// struct Gallery {
+///     imagekit: Arc<dyn ImageKitApi>,
+/// }
+///
+/// let gallery = Gallery {
+///     imagekit: Arc::new(ImageKit::from_env()?),
+/// };
+/// ```
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ImageKitApi: Send + Sync {
+    /// Uploads a file with the provided `Options`
+    async fn upload_file(&self, opts: Options) -> Result<Response>;
+
+    /// Deletes the file with the provided File ID
+    async fn delete_file(&self, file_id: &str) -> Result<()>;
+
+    /// Given a file id retrieves the information within
+    async fn file_details(&self, file_id: &str) -> Result<Response>;
+}
+
+#[async_trait]
+impl<T> ImageKitApi for T
+where
+    T: Upload + Delete + Details + Send + Sync,
+{
+    async fn upload_file(&self, opts: Options) -> Result<Response> {
+        Upload::upload(self, opts).await
+    }
+
+    async fn delete_file(&self, file_id: &str) -> Result<()> {
+        Delete::delete(self, file_id).await
+    }
+
+    async fn file_details(&self, file_id: &str) -> Result<Response> {
+        Details::get_file_details(self, file_id).await
+    }
+}
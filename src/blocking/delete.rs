@@ -0,0 +1,23 @@
+use anyhow::Result;
+
+use crate::client::check_response;
+use crate::delete::request;
+use crate::metrics::Operation;
+
+use super::ImageKit;
+
+pub trait Delete {
+    /// Deletes the file with the provided File ID
+    fn delete<T: ToString>(&self, file_id: T) -> Result<()>;
+}
+
+impl Delete for ImageKit {
+    fn delete<T: ToString>(&self, file_id: T) -> Result<()> {
+        let response = self.send(
+            Operation::Delete,
+            request(&self.files_endpoint(), &file_id.to_string()),
+        )?;
+
+        check_response(&response, 204)
+    }
+}
@@ -0,0 +1,26 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+
+use crate::client::parse_response;
+use crate::management::file_details::request;
+use crate::metrics::Operation;
+use crate::upload::types::Response;
+
+use super::ImageKit;
+
+pub trait Details {
+    /// Given a file id retrieves the information within
+    fn get_file_details<T: AsRef<str> + Display>(&self, id: T) -> Result<Response>;
+}
+
+impl Details for ImageKit {
+    fn get_file_details<T: AsRef<str> + Display>(&self, id: T) -> Result<Response> {
+        let response = self.send(
+            Operation::GetFileDetails,
+            request(&self.files_endpoint(), id.as_ref()),
+        )?;
+
+        parse_response(&response, 200)
+    }
+}
@@ -0,0 +1,261 @@
+mod delete;
+mod file_details;
+mod upload;
+
+use std::fmt;
+use std::future::Future;
+use std::io::{self, Read};
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use anyhow::{bail, Result};
+use bytes::Bytes;
+use futures_util::StreamExt;
+use reqwest::blocking::Client;
+
+use crate::client::{authenticate, authorization, env, API_ENDPOINT};
+use crate::http::{reqwest_parts, response_headers, Body, BodyStream, HttpRequest, HttpResponse};
+use crate::metrics::{MetricsObserver, Operation, RequestMetrics};
+use crate::types::Secret;
+use crate::upload::UPLOAD_ENDPOINT;
+use crate::url::parse::parse_url;
+use crate::url::{generate_url, Options as UrlOptions, Transformation, Url};
+
+pub use self::delete::Delete;
+pub use self::file_details::Details;
+pub use self::upload::{Options, Upload, UploadFile};
+
+/// A synchronous ImageKit.io API Client Instance, available with the
+/// `blocking` feature.
+///
+/// Mirrors the async `imagekit::ImageKit` for programs without an async
+/// runtime, it must not be used from within one.
+pub struct ImageKit {
+    pub(crate) public_key: String,
+    /// `Authorization` header built from the private key, `None` for
+    /// clients created with only the public key
+    pub(crate) authorization: Option<Secret>,
+    pub(crate) url_endpoint: String,
+    /// Base URL of the files API
+    pub(crate) api_endpoint: String,
+    /// Endpoint used by uploads which do not set their own
+    pub(crate) upload_endpoint: String,
+    pub(crate) client: Client,
+    /// Receives the outcome of every request, when set
+    pub(crate) observer: Option<Arc<dyn MetricsObserver>>,
+}
+
+impl ImageKit {
+    pub fn new<T: ToString>(public_key: T, private_key: T, url_endpoint: T) -> Result<Self> {
+        let private_key = Secret::new(private_key.to_string());
+        let mut imagekit = Self::public(public_key, url_endpoint)?;
+
+        imagekit.authorization = Some(authorization(private_key.expose()));
+        Ok(imagekit)
+    }
+
+    /// Creates a new instance of `ImageKit` holding only the public key,
+    /// which can only upload files with `AuthenticationParameters` minted
+    /// by a backend
+    pub fn public<T: ToString>(public_key: T, url_endpoint: T) -> Result<Self> {
+        let client = Client::builder().build()?;
+
+        Ok(Self {
+            public_key: public_key.to_string(),
+            authorization: None,
+            url_endpoint: url_endpoint.to_string(),
+            api_endpoint: API_ENDPOINT.to_string(),
+            upload_endpoint: UPLOAD_ENDPOINT.to_string(),
+            client,
+            observer: None,
+        })
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let public_key = env("IMAGEKIT_PUBLIC_KEY")?;
+        let private_key = env("IMAGEKIT_PRIVATE_KEY")?;
+        let url_endpoint = env("IMAGEKIT_URL_ENDPOINT")?;
+        let imagekit = Self::new(public_key, private_key, url_endpoint)?;
+
+        Ok(imagekit)
+    }
+
+    /// Sets the base URL of the ImageKit API, `https://api.imagekit.io` by
+    /// default
+    pub fn api_endpoint<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.api_endpoint = val.into();
+        self
+    }
+
+    /// Sets the endpoint files are uploaded to when the upload `Options` do
+    /// not set one
+    pub fn upload_endpoint<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.upload_endpoint = val.into();
+        self
+    }
+
+    /// Sets the `MetricsObserver` receiving the outcome of every request
+    pub fn metrics_observer<M: MetricsObserver + 'static>(mut self, val: M) -> Self {
+        self.observer = Some(Arc::new(val));
+        self
+    }
+
+    pub(crate) fn files_endpoint(&self) -> String {
+        format!("{}/v1/files", self.api_endpoint.trim_end_matches('/'))
+    }
+
+    /// Sends a request authenticated with the private key
+    pub(crate) fn send(&self, operation: Operation, request: HttpRequest) -> Result<HttpResponse> {
+        let Some(authorization) = &self.authorization else {
+            bail!("The private key is required, this client only holds the public key");
+        };
+
+        self.dispatch(operation, authenticate(request, authorization))
+    }
+
+    /// Sends a request with the blocking `reqwest` client, notifying the
+    /// `MetricsObserver` of its outcome
+    pub(crate) fn dispatch(
+        &self,
+        operation: Operation,
+        request: HttpRequest,
+    ) -> Result<HttpResponse> {
+        let Some(observer) = &self.observer else {
+            return self.transmit(operation, request);
+        };
+
+        let bytes_sent = request.body.len();
+        let start = std::time::Instant::now();
+        let response = self.transmit(operation, request);
+
+        observer.on_request(&RequestMetrics::new(
+            operation,
+            &response,
+            start.elapsed(),
+            bytes_sent,
+        ));
+        response
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    fn transmit(&self, _: Operation, request: HttpRequest) -> Result<HttpResponse> {
+        self.execute(request)
+    }
+
+    /// Sends a request with the blocking `reqwest` client within its
+    /// `imagekit.request` span
+    #[cfg(feature = "tracing")]
+    fn transmit(&self, operation: Operation, request: HttpRequest) -> Result<HttpResponse> {
+        let span = crate::http::request_span(operation, &request);
+        let _entered = span.enter();
+        let start = std::time::Instant::now();
+        let response = self.execute(request);
+
+        crate::http::record_response(&span, &response, start);
+        response
+    }
+
+    fn execute(&self, mut request: HttpRequest) -> Result<HttpResponse> {
+        let (method, headers) = reqwest_parts(&request)?;
+        let body = match request.take_body() {
+            Body::Bytes(bytes) => reqwest::blocking::Body::from(bytes),
+            Body::Stream { stream, length } => {
+                let reader = StreamReader {
+                    stream,
+                    chunk: Bytes::new(),
+                };
+
+                reqwest::blocking::Body::sized(reader, length)
+            }
+        };
+        let response = self
+            .client
+            .request(method, &request.url)
+            .headers(headers)
+            .body(body)
+            .send()?;
+        let status = response.status().as_u16();
+        let headers = response_headers(response.headers());
+        let body = response.bytes()?.to_vec();
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    /// Parses a URL generated by ImageKit back into the `Options` used to
+    /// generate it and its chain of transformations
+    pub fn parse_url<T: AsRef<str>>(&self, url: T) -> Result<(UrlOptions, Vec<Transformation>)> {
+        parse_url(&self.url_endpoint, url.as_ref())
+    }
+}
+
+/// Reads a streamed `Body` for the blocking `reqwest` client, waiting on
+/// the current thread for each chunk
+struct StreamReader {
+    stream: BodyStream,
+    /// Remaining Bytes of the last chunk
+    chunk: Bytes,
+}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.chunk.is_empty() {
+            match block_on(self.stream.next()) {
+                Some(chunk) => self.chunk = chunk?,
+                None => return Ok(0),
+            }
+        }
+
+        let read = buf.len().min(self.chunk.len());
+
+        buf[..read].copy_from_slice(&self.chunk.split_to(read));
+        Ok(read)
+    }
+}
+
+/// Wakes the thread polling a future in `block_on`
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `future` to completion, parking the current thread while it is
+/// pending
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+
+        thread::park();
+    }
+}
+
+/// Shows only the public key and the URL endpoint, the private key is never
+/// formatted
+impl fmt::Debug for ImageKit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageKit")
+            .field("public_key", &self.public_key)
+            .field("url_endpoint", &self.url_endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Url for ImageKit {
+    fn url(&self, opts: UrlOptions) -> Result<String> {
+        generate_url(&self.url_endpoint, opts)
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::task::Poll;
+
+use anyhow::Result;
+use bytes::Bytes;
+use futures_util::stream;
+
+use crate::client::parse_response;
+use crate::http::Body;
+use crate::metrics::Operation;
+use crate::upload::request;
+use crate::upload::types::Response;
+
+use super::ImageKit;
+
+/// Size of the chunks files are read in while uploading
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// File to upload with the blocking client, files are streamed from the
+/// current position when uploading
+pub enum UploadFile {
+    Binary(File),
+    Bytes(Vec<u8>),
+}
+
+impl UploadFile {
+    /// Body of the upload request, files are read in chunks while the request
+    /// is sent
+    fn into_body(self) -> Result<Body> {
+        match self {
+            UploadFile::Binary(mut file) => {
+                let length = file.metadata()?.len() - file.stream_position()?;
+                let mut buffer = vec![0; CHUNK_SIZE];
+                let stream = stream::poll_fn(move |_| {
+                    Poll::Ready(match file.read(&mut buffer) {
+                        Ok(0) => None,
+                        Ok(read) => Some(Ok(Bytes::copy_from_slice(&buffer[..read]))),
+                        Err(err) => Some(Err(err)),
+                    })
+                });
+
+                Ok(Body::Stream {
+                    stream: Box::pin(stream),
+                    length,
+                })
+            }
+            UploadFile::Bytes(file_bytes) => Ok(Body::Bytes(file_bytes)),
+        }
+    }
+}
+
+impl From<File> for UploadFile {
+    fn from(file: File) -> Self {
+        UploadFile::Binary(file)
+    }
+}
+
+impl From<Vec<u8>> for UploadFile {
+    fn from(value: Vec<u8>) -> Self {
+        UploadFile::Bytes(value)
+    }
+}
+
+/// Upload `Options` of the blocking client, shared with the async client
+/// but holding a blocking `UploadFile`.
+pub type Options = crate::upload::Options<UploadFile>;
+
+pub trait Upload {
+    /// Uploads an image with the provided `Options`
+    fn upload(&self, opts: Options) -> Result<Response>;
+}
+
+impl Upload for ImageKit {
+    fn upload(&self, opts: Options) -> Result<Response> {
+        let data = opts.file.into_body()?;
+        let endpoint = opts.endpoint.as_deref().unwrap_or(&self.upload_endpoint);
+        let response = match &opts.authentication {
+            Some(params) => {
+                let client = Some((self.public_key.as_str(), params));
+
+                self.dispatch(
+                    Operation::Upload,
+                    request(
+                        endpoint,
+                        &opts.file_name,
+                        data,
+                        &opts.response_fields,
+                        client,
+                    ),
+                )?
+            }
+            None => self.send(
+                Operation::Upload,
+                request(endpoint, &opts.file_name, data, &opts.response_fields, None),
+            )?,
+        };
+
+        parse_response(&response, 200)
+    }
+}
@@ -1,11 +1,22 @@
-use std::env::var;
+use std::fmt;
+use std::sync::Arc;
 
 use anyhow::{bail, Result};
-use http_auth_basic::Credentials;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
-use reqwest::{Client, ClientBuilder};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use zeroize::Zeroize;
 
+use crate::http::{HttpRequest, HttpResponse, HttpTransport};
+use crate::metrics::{MetricsObserver, Operation, RequestMetrics};
+use crate::types::Secret;
+use crate::upload::UPLOAD_ENDPOINT;
+use crate::ErrorResponse;
+
+/// Base URL of the ImageKit API
+pub const API_ENDPOINT: &str = "https://api.imagekit.io";
 pub const FILES_ENDPOINT: &str = "https://api.imagekit.io/v1/files";
+pub const ACCOUNTS_ENDPOINT: &str = "https://api.imagekit.io/v1/accounts";
 
 /// An ImageKit.io API Client Instance
 ///
@@ -20,47 +31,278 @@ pub const FILES_ENDPOINT: &str = "https://api.imagekit.io/v1/files";
 /// ```
 ///
 /// If you want to set a custom upload endpoint, you can use the
-/// `upload_endpoint` method.
+/// `upload_endpoint` method. Similarly, `api_endpoint` sets the base URL of
+/// the other APIs, e.g. to send requests to a mock server.
+///
+/// Requests are sent with `reqwest` by default, use `with_transport` to
+/// provide another `HttpTransport`.
+///
+/// Clients which must not hold the private key, e.g. mobile or desktop
+/// apps, are created with `public` and can only upload files with
+/// `AuthenticationParameters` minted by a backend.
 pub struct ImageKit {
-    #[allow(dead_code)]
     pub(crate) public_key: String,
-    #[allow(dead_code)]
-    pub(crate) private_key: String,
-    #[allow(dead_code)]
+    /// `None` for clients created with only the public key
+    pub(crate) private_key: Option<Secret>,
+    /// `Authorization` header built from the private key
+    pub(crate) authorization: Option<Secret>,
     pub(crate) url_endpoint: String,
-    pub(crate) client: Client,
+    /// Base URL of the files and accounts APIs
+    pub(crate) api_endpoint: String,
+    /// Endpoint used by uploads which do not set their own
+    pub(crate) upload_endpoint: String,
+    pub(crate) transport: Arc<dyn HttpTransport>,
+    /// Receives the outcome of every request, when set
+    pub(crate) observer: Option<Arc<dyn MetricsObserver>>,
 }
 
 impl ImageKit {
+    #[cfg(feature = "reqwest-transport")]
     pub fn new<T: ToString>(public_key: T, private_key: T, url_endpoint: T) -> Result<Self> {
-        let creds = Credentials::new(&private_key.to_string(), "").as_http_header();
-        let mut headers = HeaderMap::new();
+        let transport = crate::http::ReqwestTransport::new()?;
 
-        headers.insert(AUTHORIZATION, HeaderValue::from_str(&creds)?);
+        Ok(Self::with_transport(
+            public_key,
+            private_key,
+            url_endpoint,
+            transport,
+        ))
+    }
 
-        let client = ClientBuilder::new().default_headers(headers).build()?;
+    /// Creates a new instance of `ImageKit` sending its requests through
+    /// the provided `HttpTransport`
+    pub fn with_transport<T: ToString, H: HttpTransport + 'static>(
+        public_key: T,
+        private_key: T,
+        url_endpoint: T,
+        transport: H,
+    ) -> Self {
+        let private_key = Secret::new(private_key.to_string());
 
-        Ok(Self {
+        Self {
             public_key: public_key.to_string(),
-            private_key: private_key.to_string(),
+            authorization: Some(authorization(private_key.expose())),
+            private_key: Some(private_key),
             url_endpoint: url_endpoint.to_string(),
-            client,
-        })
+            api_endpoint: API_ENDPOINT.to_string(),
+            upload_endpoint: UPLOAD_ENDPOINT.to_string(),
+            transport: Arc::new(transport),
+            observer: None,
+        }
     }
 
+    /// Creates a new instance of `ImageKit` holding only the public key
+    #[cfg(feature = "reqwest-transport")]
+    pub fn public<T: ToString>(public_key: T, url_endpoint: T) -> Result<Self> {
+        let transport = crate::http::ReqwestTransport::new()?;
+
+        Ok(Self::public_with_transport(
+            public_key,
+            url_endpoint,
+            transport,
+        ))
+    }
+
+    /// Creates a new instance of `ImageKit` holding only the public key and
+    /// sending its requests through the provided `HttpTransport`
+    pub fn public_with_transport<T: ToString, H: HttpTransport + 'static>(
+        public_key: T,
+        url_endpoint: T,
+        transport: H,
+    ) -> Self {
+        Self {
+            public_key: public_key.to_string(),
+            private_key: None,
+            authorization: None,
+            url_endpoint: url_endpoint.to_string(),
+            api_endpoint: API_ENDPOINT.to_string(),
+            upload_endpoint: UPLOAD_ENDPOINT.to_string(),
+            transport: Arc::new(transport),
+            observer: None,
+        }
+    }
+
+    #[cfg(feature = "reqwest-transport")]
     pub fn from_env() -> Result<Self> {
-        let public_key = ImageKit::env("IMAGEKIT_PUBLIC_KEY")?;
-        let private_key = ImageKit::env("IMAGEKIT_PRIVATE_KEY")?;
-        let url_endpoint = ImageKit::env("IMAGEKIT_URL_ENDPOINT")?;
+        let public_key = env("IMAGEKIT_PUBLIC_KEY")?;
+        let private_key = env("IMAGEKIT_PRIVATE_KEY")?;
+        let url_endpoint = env("IMAGEKIT_URL_ENDPOINT")?;
         let imagekit = Self::new(public_key, private_key, url_endpoint)?;
 
         Ok(imagekit)
     }
 
-    fn env(key: &str) -> Result<String> {
-        match var(key) {
-            Ok(value) => Ok(value),
-            Err(err) => bail!(err),
-        }
+    /// Sets the base URL of the ImageKit API, `https://api.imagekit.io` by
+    /// default
+    pub fn api_endpoint<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.api_endpoint = val.into();
+        self
+    }
+
+    /// Sets the endpoint files are uploaded to when the upload `Options` do
+    /// not set one
+    pub fn upload_endpoint<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.upload_endpoint = val.into();
+        self
+    }
+
+    pub(crate) fn files_endpoint(&self) -> String {
+        format!("{}/v1/files", self.api_endpoint.trim_end_matches('/'))
+    }
+
+    pub(crate) fn accounts_endpoint(&self) -> String {
+        format!("{}/v1/accounts", self.api_endpoint.trim_end_matches('/'))
+    }
+
+    /// Sets the `MetricsObserver` receiving the outcome of every request
+    pub fn metrics_observer<M: MetricsObserver + 'static>(mut self, val: M) -> Self {
+        self.observer = Some(Arc::new(val));
+        self
+    }
+
+    /// Sends a request authenticated with the private key through the
+    /// transport
+    pub(crate) async fn send(
+        &self,
+        operation: Operation,
+        request: HttpRequest,
+    ) -> Result<HttpResponse> {
+        let Some(authorization) = &self.authorization else {
+            bail!("The private key is required, this client only holds the public key");
+        };
+
+        self.dispatch(operation, authenticate(request, authorization))
+            .await
+    }
+
+    /// Sends a request through the transport once, notifying the
+    /// `MetricsObserver` of its outcome. Requests are not retried, refer to
+    /// `crate::metrics`.
+    pub(crate) async fn dispatch(
+        &self,
+        operation: Operation,
+        request: HttpRequest,
+    ) -> Result<HttpResponse> {
+        let Some(observer) = &self.observer else {
+            return self.transmit(operation, request).await;
+        };
+
+        let bytes_sent = request.body.len();
+        let start = std::time::Instant::now();
+        let response = self.transmit(operation, request).await;
+
+        observer.on_request(&RequestMetrics::new(
+            operation,
+            &response,
+            start.elapsed(),
+            bytes_sent,
+        ));
+        response
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    async fn transmit(&self, _: Operation, request: HttpRequest) -> Result<HttpResponse> {
+        self.transport.send(request).await
+    }
+
+    /// Sends a request through the transport within its `imagekit.request`
+    /// span
+    #[cfg(feature = "tracing")]
+    async fn transmit(&self, operation: Operation, request: HttpRequest) -> Result<HttpResponse> {
+        use tracing::Instrument;
+
+        let span = crate::http::request_span(operation, &request);
+        let start = std::time::Instant::now();
+        let response = self.transport.send(request).instrument(span.clone()).await;
+
+        crate::http::record_response(&span, &response, start);
+        response
+    }
+}
+
+/// Shows only the public key and the URL endpoint, the private key is never
+/// formatted
+impl fmt::Debug for ImageKit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageKit")
+            .field("public_key", &self.public_key)
+            .field("url_endpoint", &self.url_endpoint)
+            .finish_non_exhaustive()
     }
 }
+
+#[cfg(feature = "reqwest-transport")]
+pub(crate) fn env(key: &str) -> Result<String> {
+    match std::env::var(key) {
+        Ok(value) => Ok(value),
+        Err(err) => bail!(err),
+    }
+}
+
+/// Builds the `Authorization` header sent on every API request, a Basic
+/// authentication with the private key as user and no password. Buffers
+/// holding the key are zeroed and sized upfront so they are not reallocated.
+pub(crate) fn authorization(private_key: &str) -> Secret {
+    let mut credentials = String::with_capacity(private_key.len() + 1);
+
+    credentials.push_str(private_key);
+    credentials.push(':');
+
+    let mut header = String::with_capacity(6 + credentials.len().div_ceil(3) * 4);
+
+    header.push_str("Basic ");
+    STANDARD.encode_string(&credentials, &mut header);
+    credentials.zeroize();
+
+    Secret::new(header)
+}
+
+/// Adds the `Authorization` header built by `authorization`, its copy in the
+/// request is zeroed when the request is dropped
+pub(crate) fn authenticate(request: HttpRequest, authorization: &Secret) -> HttpRequest {
+    request.header("Authorization", authorization.expose())
+}
+
+/// Deserializes the body of a response with the `expected` status, the
+/// message of the `ErrorResponse` is returned as error otherwise
+pub(crate) fn parse_response<T: DeserializeOwned>(
+    response: &HttpResponse,
+    expected: u16,
+) -> Result<T> {
+    check_response(response, expected)?;
+
+    Ok(serde_json::from_slice(&response.body)?)
+}
+
+/// Checks the status of a response without body, e.g. deletions
+pub(crate) fn check_response(response: &HttpResponse, expected: u16) -> Result<()> {
+    if response.status == expected {
+        return Ok(());
+    }
+
+    error_response(response)
+}
+
+/// Deserializes the body of a response with any `2xx` status, for APIs
+/// which do not document the exact status of each call
+pub(crate) fn parse_success<T: DeserializeOwned>(response: &HttpResponse) -> Result<T> {
+    check_success(response)?;
+
+    Ok(serde_json::from_slice(&response.body)?)
+}
+
+/// Checks that a response without body has any `2xx` status
+pub(crate) fn check_success(response: &HttpResponse) -> Result<()> {
+    if (200..300).contains(&response.status) {
+        return Ok(());
+    }
+
+    error_response(response)
+}
+
+/// Returns the message of the `ErrorResponse` in the body as error
+fn error_response(response: &HttpResponse) -> Result<()> {
+    let result = serde_json::from_slice::<ErrorResponse>(&response.body)?;
+
+    bail!(result.message);
+}
@@ -1,9 +1,10 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::{StatusCode, Url};
 
-use crate::client::FILES_ENDPOINT;
-use crate::{ErrorResponse, ImageKit};
+use crate::client::check_response;
+use crate::http::{segment, HttpRequest, Method};
+use crate::metrics::Operation;
+use crate::ImageKit;
 
 #[async_trait]
 pub trait Delete {
@@ -13,17 +14,31 @@ pub trait Delete {
 
 #[async_trait]
 impl Delete for ImageKit {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "imagekit.delete",
+            skip_all,
+            fields(file_id = %file_id.to_string()),
+            err
+        )
+    )]
     async fn delete<T: ToString + Send>(&self, file_id: T) -> Result<()> {
-        let url_string = format!("{}/{}", FILES_ENDPOINT, file_id.to_string());
-        let endpoint_url = Url::parse(&url_string).unwrap();
-        let response = self.client.delete(endpoint_url).send().await.unwrap();
+        let response = self
+            .send(
+                Operation::Delete,
+                request(&self.files_endpoint(), &file_id.to_string()),
+            )
+            .await?;
 
-        if matches!(response.status(), StatusCode::NO_CONTENT) {
-            return Ok(());
-        }
-
-        let result = response.json::<ErrorResponse>().await.unwrap();
-
-        bail!(result.message);
+        check_response(&response, 204)
     }
 }
+
+/// Builds the request deleting the file with `file_id`
+pub(crate) fn request(files_endpoint: &str, file_id: &str) -> HttpRequest {
+    HttpRequest::new(
+        Method::Delete,
+        format!("{files_endpoint}/{}", segment(file_id)),
+    )
+}
@@ -0,0 +1,528 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::pin::Pin;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::{future, stream, Stream, StreamExt};
+use percent_encoding::{utf8_percent_encode, AsciiSet, PercentEncode, NON_ALPHANUMERIC};
+use serde::Serialize;
+use zeroize::Zeroize;
+
+/// Characters escaped in query components and in ids put in the path of
+/// requests, every character but the unreserved ones
+pub(crate) const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Escapes an id as a single path segment, so ids holding `/`, `?` or `#`
+/// can not change the requested resource
+pub(crate) fn segment(id: &str) -> PercentEncode<'_> {
+    utf8_percent_encode(id, COMPONENT)
+}
+
+/// HTTP method of an `HttpRequest`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+        }
+    }
+}
+
+/// Request to the ImageKit API built by the client, independent of the
+/// HTTP stack sending it. The body is already encoded and its content type,
+/// when any, is included in the headers, along with its length when it is
+/// streamed.
+///
+/// The `Authorization` header and the body, which may hold upload
+/// signatures, are redacted when formatted with `Debug`, and the
+/// `Authorization` header is zeroed in memory when the request is dropped.
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Body,
+}
+
+impl HttpRequest {
+    pub fn new<T: Into<String>>(method: Method, url: T) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: Body::default(),
+        }
+    }
+
+    /// Appends a header to the request
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets a JSON body
+    pub fn json<T: Serialize + ?Sized>(mut self, val: &T) -> Result<Self> {
+        self.body = Body::Bytes(serde_json::to_vec(val)?);
+
+        Ok(self.header("Content-Type", "application/json"))
+    }
+
+    /// Takes the body out of the request, e.g. for transports to send it
+    /// while keeping the request, which can not be destructured as it
+    /// zeroes its `Authorization` header when dropped
+    pub fn take_body(&mut self) -> Body {
+        std::mem::take(&mut self.body)
+    }
+
+    /// Sets a `multipart/form-data` body, streamed when any of its files is
+    pub fn multipart(mut self, form: Form) -> Self {
+        let (content_type, body) = form.encode();
+
+        if let Body::Stream { length, .. } = &body {
+            self = self.header("Content-Length", length.to_string());
+        }
+
+        self.body = body;
+        self.header("Content-Type", content_type)
+    }
+}
+
+impl fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(key, value)| {
+                if key.eq_ignore_ascii_case("Authorization") {
+                    (key.as_str(), "[REDACTED]")
+                } else {
+                    (key.as_str(), value.as_str())
+                }
+            })
+            .collect();
+
+        f.debug_struct("HttpRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &headers)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+impl Drop for HttpRequest {
+    fn drop(&mut self) {
+        for (key, value) in &mut self.headers {
+            if key.eq_ignore_ascii_case("Authorization") {
+                value.zeroize();
+            }
+        }
+    }
+}
+
+/// Chunks of a `Body::Stream`
+pub type BodyStream = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send + Sync>>;
+
+/// Body of an `HttpRequest`
+pub enum Body {
+    /// Body held in memory
+    Bytes(Vec<u8>),
+    /// Body read while the request is sent, e.g. an uploaded file, of
+    /// `length` Bytes
+    Stream { stream: BodyStream, length: u64 },
+}
+
+impl Body {
+    /// Length of the body in Bytes
+    pub fn len(&self) -> u64 {
+        match self {
+            Body::Bytes(bytes) => bytes.len() as u64,
+            Body::Stream { length, .. } => *length,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the body when it is held in memory
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Body::Bytes(bytes) => Some(bytes),
+            Body::Stream { .. } => None,
+        }
+    }
+
+    /// Reads the whole body in memory, for transports which can not stream
+    /// it
+    pub async fn collect(self) -> io::Result<Vec<u8>> {
+        match self {
+            Body::Bytes(bytes) => Ok(bytes),
+            Body::Stream { mut stream, length } => {
+                let mut bytes = Vec::with_capacity(length as usize);
+
+                while let Some(chunk) = stream.next().await {
+                    bytes.extend_from_slice(&chunk?);
+                }
+
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Chunks of the body
+    fn into_stream(self) -> BodyStream {
+        match self {
+            Body::Bytes(bytes) => Box::pin(stream::once(future::ready(Ok(Bytes::from(bytes))))),
+            Body::Stream { stream, .. } => stream,
+        }
+    }
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Body::Bytes(Vec::new())
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    fn from(bytes: Vec<u8>) -> Self {
+        Body::Bytes(bytes)
+    }
+}
+
+/// Only the length is shown, bodies may hold upload signatures
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Body::Bytes(bytes) => write!(f, "[{} bytes]", bytes.len()),
+            Body::Stream { length, .. } => write!(f, "[stream of {length} bytes]"),
+        }
+    }
+}
+
+/// Response of the ImageKit API returned by an `HttpTransport`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Returns the first value of the header, compared case insensitively
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Sends the requests built by `ImageKit`, implement it to use the client
+/// with another async runtime or HTTP stack:
+///
+/// ```ignore
+/// let imagekit = ImageKit::with_transport(public_key, private_key, url_endpoint, MyTransport);
+/// let client = ImageKit::public_with_transport(public_key, url_endpoint, MyTransport);
+/// ```
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// Span of a request sent to the API, available with the `tracing` feature.
+/// Only the method and the URL without its query are recorded, headers and
+/// bodies hold the private key and upload signatures.
+#[cfg(feature = "tracing")]
+pub(crate) fn request_span(
+    operation: crate::metrics::Operation,
+    request: &HttpRequest,
+) -> tracing::Span {
+    let endpoint = request.url.split('?').next().unwrap_or_default();
+
+    tracing::debug_span!(
+        "imagekit.request",
+        operation = operation.as_str(),
+        method = request.method.as_str(),
+        endpoint,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+    )
+}
+
+/// Records the status and latency of a request in its `request_span`
+#[cfg(feature = "tracing")]
+pub(crate) fn record_response(
+    span: &tracing::Span,
+    response: &Result<HttpResponse>,
+    start: std::time::Instant,
+) {
+    span.record("latency_ms", start.elapsed().as_millis() as u64);
+
+    if let Ok(response) = response {
+        span.record("status", response.status);
+    }
+}
+
+/// Field of a `multipart/form-data` body.
+enum Field {
+    Text {
+        name: String,
+        value: String,
+    },
+    File {
+        name: String,
+        file_name: String,
+        content_type: String,
+        data: Body,
+    },
+}
+
+/// `multipart/form-data` body. It is encoded in memory unless a file is
+/// streamed, in which case the encoded fields are streamed along with it.
+#[derive(Default)]
+pub struct Form {
+    fields: Vec<Field>,
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.fields.push(Field::Text {
+            name: name.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    pub fn file<K, F, C, B>(mut self, name: K, file_name: F, content_type: C, data: B) -> Self
+    where
+        K: Into<String>,
+        F: Into<String>,
+        C: Into<String>,
+        B: Into<Body>,
+    {
+        self.fields.push(Field::File {
+            name: name.into(),
+            file_name: file_name.into(),
+            content_type: content_type.into(),
+            data: data.into(),
+        });
+        self
+    }
+
+    /// Returns the content type, including the boundary, and the body
+    fn encode(self) -> (String, Body) {
+        let boundary = self.boundary();
+        // Encoded fields held in memory, followed by the streamed files
+        let mut parts: Vec<Body> = Vec::new();
+        let mut body = Vec::new();
+
+        for field in self.fields {
+            body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+
+            match field {
+                Field::Text { name, value } => {
+                    body.extend_from_slice(
+                        format!(
+                            "Content-Disposition: form-data; name=\"{}\"\r\n\r\n",
+                            escape(&name)
+                        )
+                        .as_bytes(),
+                    );
+                    body.extend_from_slice(value.as_bytes());
+                }
+                Field::File {
+                    name,
+                    file_name,
+                    content_type,
+                    data,
+                } => {
+                    body.extend_from_slice(
+                        format!(
+                            "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                            Content-Type: {content_type}\r\n\r\n",
+                            escape(&name),
+                            escape(&file_name)
+                        )
+                        .as_bytes(),
+                    );
+
+                    match data {
+                        Body::Bytes(data) => body.extend_from_slice(&data),
+                        stream => {
+                            parts.push(Body::Bytes(std::mem::take(&mut body)));
+                            parts.push(stream);
+                        }
+                    }
+                }
+            }
+
+            body.extend_from_slice(b"\r\n");
+        }
+
+        body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+        let content_type = format!("multipart/form-data; boundary={boundary}");
+
+        if parts.is_empty() {
+            return (content_type, Body::Bytes(body));
+        }
+
+        parts.push(Body::Bytes(body));
+
+        let length = parts.iter().map(Body::len).sum();
+        let stream = stream::iter(parts.into_iter().map(Body::into_stream)).flatten();
+
+        (
+            content_type,
+            Body::Stream {
+                stream: Box::pin(stream),
+                length,
+            },
+        )
+    }
+
+    /// Picks a boundary not found in any of the fields held in memory.
+    /// Streamed files can not be searched, so the boundaries tried start at
+    /// a random value when the form has any.
+    fn boundary(&self) -> String {
+        let streamed = self.fields.iter().any(|field| {
+            matches!(
+                field,
+                Field::File {
+                    data: Body::Stream { .. },
+                    ..
+                }
+            )
+        });
+        let start = if streamed {
+            RandomState::new().build_hasher().finish()
+        } else {
+            0
+        };
+        let contains = |boundary: &[u8]| {
+            self.fields.iter().any(|field| {
+                let data = match field {
+                    Field::Text { value, .. } => value.as_bytes(),
+                    Field::File { data, .. } => data.as_bytes().unwrap_or_default(),
+                };
+
+                data.windows(boundary.len())
+                    .any(|window| window == boundary)
+            })
+        };
+
+        (0u64..)
+            .map(|attempt| format!("imagekit-boundary-{:016x}", start.wrapping_add(attempt)))
+            .find(|boundary| !contains(boundary.as_bytes()))
+            .expect("a boundary not found in the fields")
+    }
+}
+
+fn escape(val: &str) -> String {
+    val.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Default `HttpTransport` built on `reqwest` and `tokio`, available with
+/// the `reqwest-transport` feature.
+#[cfg(feature = "reqwest-transport")]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest-transport")]
+impl ReqwestTransport {
+    pub fn new() -> Result<Self> {
+        Ok(Self::from_client(reqwest::Client::builder().build()?))
+    }
+
+    /// Uses a `reqwest::Client` configured by the caller, e.g. with a proxy
+    /// or timeouts
+    pub fn from_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest-transport")]
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, mut request: HttpRequest) -> Result<HttpResponse> {
+        let (method, headers) = reqwest_parts(&request)?;
+        let body = match request.take_body() {
+            Body::Bytes(bytes) => reqwest::Body::from(bytes),
+            Body::Stream { stream, .. } => reqwest::Body::wrap_stream(stream),
+        };
+        let response = self
+            .client
+            .request(method, &request.url)
+            .headers(headers)
+            .body(body)
+            .send()
+            .await?;
+        let status = response.status().as_u16();
+        let headers = response_headers(response.headers());
+        let body = response.bytes().await?.to_vec();
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// Method and headers of a request for `reqwest`, the `Authorization`
+/// header is marked as sensitive
+#[cfg(feature = "reqwest-transport")]
+pub(crate) fn reqwest_parts(
+    request: &HttpRequest,
+) -> Result<(reqwest::Method, reqwest::header::HeaderMap)> {
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+    let method = reqwest::Method::from_bytes(request.method.as_str().as_bytes())?;
+    let mut headers = HeaderMap::with_capacity(request.headers.len());
+
+    for (key, value) in &request.headers {
+        let mut value = HeaderValue::from_str(value)?;
+
+        value.set_sensitive(key.eq_ignore_ascii_case("Authorization"));
+        headers.append(HeaderName::from_bytes(key.as_bytes())?, value);
+    }
+
+    Ok((method, headers))
+}
+
+/// Headers of a `reqwest` response, values which are not visible ASCII are
+/// skipped
+#[cfg(feature = "reqwest-transport")]
+pub(crate) fn response_headers(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
@@ -1,32 +1,88 @@
+pub mod accounts;
+pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
 pub mod delete;
+pub mod http;
 pub mod management;
+pub mod metrics;
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock_server;
+pub mod phash;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod types;
 pub mod upload;
 pub mod url;
+pub mod webhook;
 
+pub use accounts::{Origins, UrlEndpoints, Usage};
+pub use api::ImageKitApi;
 pub use client::ImageKit;
 pub use delete::Delete;
 pub use management::file_details;
+pub use phash::PHash;
 pub use types::ErrorResponse;
 pub use upload::Upload;
+pub use url::Responsive;
 pub use url::Transformation;
 pub use url::TransformationPosition;
 pub use url::Url;
+pub use url::UrlGenerator;
+pub use webhook::WebhookEvent;
 
+/// Fixtures shared by the test modules
 #[cfg(test)]
+mod test_utils {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::http::HttpResponse;
+
+    /// Client for the `demo` URL endpoint
+    #[cfg(feature = "reqwest-transport")]
+    pub fn imagekit() -> super::ImageKit {
+        super::ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap()
+    }
+
+    /// The `assets/ferris.jpeg` image
+    #[cfg(feature = "reqwest-transport")]
+    pub fn ferris() -> super::upload::UploadFile {
+        super::upload::UploadFile::from(std::fs::read("assets/ferris.jpeg").unwrap())
+    }
+
+    /// Response without headers
+    pub fn response(status: u16, body: &str) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// Current time in milliseconds, as in webhook signatures
+    pub fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
 mod tests {
     use tokio::fs::File;
 
     use super::delete::Delete;
     use super::file_details::Details;
+    use super::mock_server::MockServer;
     use super::upload::types::FileType;
     use super::upload::{Options, Upload, UploadFile};
-    use super::ImageKit;
 
     #[tokio::test]
     async fn uploads_then_deletes_file() {
-        let imagekit = ImageKit::from_env().unwrap();
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
         let file = File::open("assets/ferris.jpeg").await.unwrap();
         let upload_file = UploadFile::from(file);
         let opts = Options::new(upload_file, "ferris");
@@ -46,7 +102,8 @@ mod tests {
 
     #[tokio::test]
     async fn uploads_and_retrieve_information() {
-        let imagekit = ImageKit::from_env().unwrap();
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
         let file = File::open("assets/ferris.jpeg").await.unwrap();
         let upload_file = UploadFile::from(file);
         let opts = Options::new(upload_file, "ferris");
@@ -56,7 +113,7 @@ mod tests {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "reqwest-transport"))]
 mod url_tests {
     use super::url::Options;
     use super::ImageKit;
@@ -65,11 +122,12 @@ mod url_tests {
 
     #[tokio::test]
     async fn url_transformation_for_path() {
-        let imagekit = ImageKit::from_env().unwrap();
+        let imagekit =
+            ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap();
         let transformation = Transformation::new()
             .width(200)
             .height(200)
-            .aspect_raio("1-1");
+            .aspect_ratio(1, 1);
         let options = Options::new(transformation).path("ferris_cTgKr8mAO");
         let result = imagekit.url(options);
         assert!(result.is_ok());
@@ -77,8 +135,9 @@ mod url_tests {
 
     #[tokio::test]
     async fn url_transformation_for_aspect_ratio() {
-        let imagekit = ImageKit::from_env().unwrap();
-        let transformation = Transformation::new().aspect_raio("2-1").height(200);
+        let imagekit =
+            ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap();
+        let transformation = Transformation::new().aspect_ratio(2, 1).height(200);
         let options = Options::new(transformation).path("ferris_cTgKr8mAO");
         let result = imagekit.url(options);
         assert!(result.is_ok());
@@ -86,7 +145,8 @@ mod url_tests {
 
     #[tokio::test]
     async fn url_transformation_for_query() {
-        let imagekit = ImageKit::from_env().unwrap();
+        let imagekit =
+            ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap();
         let transformation = Transformation::new().width(200);
         let options = Options::new(transformation)
             .path("ferris_cTgKr8mAO")
@@ -97,7 +157,8 @@ mod url_tests {
 
     #[tokio::test]
     async fn url_transformation_for_src() {
-        let imagekit = ImageKit::from_env().unwrap();
+        let imagekit =
+            ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap();
         let transformation = Transformation::new().width(200);
         let src = format!("{}/ferris", imagekit.url_endpoint);
         let options = Options::new(transformation).src(src);
@@ -105,3 +166,2854 @@ mod url_tests {
         assert!(result.is_ok());
     }
 }
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod query_tests {
+    use super::test_utils::imagekit;
+    use super::url::Options;
+    use super::Transformation;
+    use super::TransformationPosition;
+    use super::Url;
+
+    #[test]
+    fn encodes_query_parameters_in_order() {
+        let options = Options::new(Transformation::new().width(300))
+            .path("ferris.jpeg")
+            .query_parameters(vec![("z", "last one"), ("a", "x&y=z")])
+            .query_parameter("ñ", "ü")
+            .sdk_version();
+
+        assert_eq!(
+            imagekit().url(options).unwrap(),
+            format!(
+                "https://ik.imagekit.io/demo/tr:w-300/ferris.jpeg\
+                ?z=last%20one&a=x%26y%3Dz&%C3%B1=%C3%BC&ik-sdk-version=rust-{}",
+                env!("CARGO_PKG_VERSION").replace('+', "%2B")
+            )
+        );
+    }
+
+    #[test]
+    fn encodes_unicode_paths() {
+        let options = Options::new(Transformation::new().width(300))
+            .path("/photos/año nuevo?#1.jpeg")
+            .transformation_position(TransformationPosition::Query);
+
+        assert_eq!(
+            imagekit().url(options).unwrap(),
+            "https://ik.imagekit.io/demo/photos/a%C3%B1o%20nuevo%3F%231.jpeg?tr=w-300"
+        );
+    }
+
+    #[test]
+    fn merges_src_query_string() {
+        let options = Options::new(Transformation::new().width(300))
+            .src("https://cdn.example.com/ferris.jpeg?v=2&s=a%20b")
+            .query_parameter("cache", "1");
+
+        assert_eq!(
+            imagekit().url(options).unwrap(),
+            "https://cdn.example.com/ferris.jpeg?v=2&s=a%20b&tr=w-300&cache=1"
+        );
+    }
+
+    #[test]
+    fn requires_path_or_src() {
+        let options = Options::new(Transformation::new().width(300))
+            .transformation_position(TransformationPosition::Query);
+
+        assert!(imagekit().url(options).is_err());
+    }
+
+    #[test]
+    fn rejects_reserved_tr_query_parameter() {
+        let options = |position| {
+            Options::new(Transformation::new().width(300))
+                .path("ferris.jpeg")
+                .query_parameter("tr", "w-100")
+                .transformation_position(position)
+        };
+
+        assert!(imagekit()
+            .url(options(TransformationPosition::Query))
+            .is_err());
+        assert!(imagekit()
+            .url(options(TransformationPosition::Path))
+            .is_err());
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod chain_tests {
+    use super::test_utils::imagekit;
+    use super::url::Options;
+    use super::Transformation;
+    use super::TransformationPosition;
+    use super::Url;
+
+    #[test]
+    fn chains_transformations_in_path() {
+        let options = Options::new(Transformation::new().width(400).height(300))
+            .chain(Transformation::new().aspect_ratio(4, 3))
+            .path("ferris.jpeg");
+        let result = imagekit().url(options).unwrap();
+
+        assert_eq!(
+            result,
+            "https://ik.imagekit.io/demo/tr:w-400,h-300:ar-4-3/ferris.jpeg"
+        );
+    }
+
+    #[test]
+    fn chains_transformations_in_query() {
+        let options = Options::new(Transformation::new().width(400))
+            .transformations(vec![
+                Transformation::new().width(400),
+                Transformation::new().height(300),
+            ])
+            .path("ferris.jpeg")
+            .transformation_position(TransformationPosition::Query);
+        let result = imagekit().url(options).unwrap();
+
+        assert_eq!(
+            result,
+            "https://ik.imagekit.io/demo/ferris.jpeg?tr=w-400:h-300"
+        );
+    }
+
+    #[test]
+    fn fails_on_empty_step() {
+        let chain =
+            Transformation::chain(&[Transformation::new().width(200), Transformation::new()]);
+
+        assert!(chain.is_err());
+        assert!(Transformation::chain(&[]).is_err());
+    }
+}
+
+#[cfg(test)]
+mod transformation_tests {
+    use super::url::{
+        Border, Crop, CropMode, Dpr, Flip, Focus, Format, Gradient, Radius, Rotation, Shadow,
+        Sharpen, Trim, UnsharpMask,
+    };
+    use super::Transformation;
+
+    #[test]
+    fn transforms_resize_and_crop() {
+        let transformation = Transformation::new()
+            .width(300)
+            .height(200)
+            .crop(Crop::AtMax)
+            .crop_mode(CropMode::PadResize)
+            .dpr(Dpr::Value(2.0));
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "w-300,h-200,c-at_max,cm-pad_resize,dpr-2"
+        );
+    }
+
+    #[test]
+    fn transforms_focus_and_coordinates() {
+        let extract = Transformation::new()
+            .width(100)
+            .height(100)
+            .crop_mode(CropMode::Extract)
+            .x_center(50)
+            .y_center(80);
+        let face = Transformation::new()
+            .relative_width(0.5)
+            .focus(Focus::Face)
+            .zoom(0.75);
+        let object = Transformation::new().focus(Focus::Object("dog".into()));
+
+        assert_eq!(
+            extract.transform().unwrap(),
+            "w-100,h-100,cm-extract,xc-50,yc-80"
+        );
+        assert_eq!(face.transform().unwrap(), "w-0.5,fo-face,z-0.75");
+        assert_eq!(object.transform().unwrap(), "fo-dog");
+    }
+
+    #[test]
+    fn transforms_format_and_quality() {
+        let transformation = Transformation::new()
+            .format(Format::Avif)
+            .quality(80)
+            .lossless(false)
+            .progressive(true)
+            .metadata(false)
+            .color_profile(true);
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "f-avif,q-80,lo-false,pr-true,md-false,cp-true"
+        );
+    }
+
+    #[test]
+    fn transforms_enhancements() {
+        let transformation = Transformation::new()
+            .blur(10)
+            .grayscale()
+            .sharpen(Sharpen::Amount(5))
+            .unsharp_mask(UnsharpMask::new(2.0, 2.0, 0.8, 0.024))
+            .contrast()
+            .shadow(Shadow::new().blur(15).offset_y(-5))
+            .gradient(Gradient::new().direction(45).from("red").to("FFFFFF"));
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "bl-10,e-grayscale,e-sharpen-5,e-usm-2-2-0.8-0.024,e-contrast,e-shadow-bl-15_y-N5,e-gradient-ld-45_from-red_to-FFFFFF"
+        );
+    }
+
+    #[test]
+    fn transforms_geometry_effects() {
+        let transformation = Transformation::new()
+            .background("F3F3F3")
+            .border(Border::new(5, "FF0000"))
+            .radius(Radius::Max)
+            .rotate(Rotation::Degrees(-90))
+            .flip(Flip::Both)
+            .trim(Trim::Default);
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "bg-F3F3F3,b-5_FF0000,r-max,rt-N90,fl-h_v,t-true"
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_values() {
+        assert!(Transformation::new().quality(0).transform().is_err());
+        assert!(Transformation::new().quality(101).transform().is_err());
+        assert!(Transformation::new().blur(0).transform().is_err());
+        assert!(Transformation::new()
+            .trim(Trim::Threshold(100))
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .background("#FFF")
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .shadow(Shadow::new().saturation(150))
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .relative_width(1.5)
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .dpr(Dpr::Value(6.0))
+            .transform()
+            .is_err());
+        assert!(Transformation::new().zoom(0.0).transform().is_err());
+        assert!(Transformation::new().zoom(f32::NAN).transform().is_err());
+        assert!(Transformation::new()
+            .zoom(f32::INFINITY)
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .unsharp_mask(UnsharpMask::new(f32::NAN, 2.0, 0.8, 0.024))
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .unsharp_mask(UnsharpMask::new(2.0, 2.0, f32::INFINITY, 0.024))
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .focus(Focus::Object("a dog".into()))
+            .transform()
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod layer_tests {
+    use super::url::{
+        ImageLayer, LayerFocus, LayerPosition, Padding, SolidLayer, TextLayer, Typography,
+    };
+    use super::Transformation;
+
+    #[test]
+    fn transforms_image_layer() {
+        let transformation = Transformation::new().width(600).layer(
+            ImageLayer::new("/logos/white.png")
+                .transformation(Transformation::new().width(100))
+                .position(LayerPosition::new().x(-10).y(20)),
+        );
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "w-600,l-image,i-logos@@white.png,w-100,lx-N10,ly-20,l-end"
+        );
+    }
+
+    #[test]
+    fn transforms_text_layer() {
+        let transformation = Transformation::new().layer(
+            TextLayer::new("Hello, World")
+                .font_size(20)
+                .font_color("FFFFFF")
+                .padding(Padding::Sides(5, 10, 5, 10))
+                .typography(Typography::BoldItalic)
+                .position(LayerPosition::new().focus(LayerFocus::BottomRight)),
+        );
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "l-text,ie-SGVsbG8sIFdvcmxk,fs-20,co-FFFFFF,pa-5_10_5_10,tg-b_i,lfo-bottom_right,l-end"
+        );
+    }
+
+    #[test]
+    fn transforms_nested_layers() {
+        let transformation = Transformation::new().layer(
+            ImageLayer::new("badge.png").transformation(
+                Transformation::new()
+                    .layer(SolidLayer::new("FF0000").width(10).height(10))
+                    .layer(TextLayer::new("New")),
+            ),
+        );
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "l-image,i-badge.png,l-solid,co-FF0000,w-10,h-10,l-end,l-text,i-New,l-end,l-end"
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_layers() {
+        assert!(Transformation::new()
+            .layer(SolidLayer::new("#FF0000"))
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .layer(TextLayer::new(""))
+            .transform()
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod ai_tests {
+    use super::url::{AiEffect, CropMode, DropShadow};
+    use super::Transformation;
+
+    #[test]
+    fn transforms_ai_effects() {
+        let transformation = Transformation::new()
+            .ai(AiEffect::BackgroundRemoval)
+            .ai(AiEffect::DropShadow(
+                DropShadow::new().azimuth(215).elevation(45).saturation(60),
+            ))
+            .ai(AiEffect::Upscale);
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "e-bgremove,e-dropshadow-az-215_el-45_st-60,e-upscale"
+        );
+    }
+
+    #[test]
+    fn escapes_prompts() {
+        let plain = Transformation::new().ai(AiEffect::ChangeBackground("snow".into()));
+        let encoded = Transformation::new().ai(AiEffect::Edit("add a hat, red".into()));
+        let fill = Transformation::new()
+            .width(800)
+            .height(400)
+            .crop_mode(CropMode::PadResize)
+            .ai(AiEffect::GenerativeFill(None));
+
+        assert_eq!(plain.transform().unwrap(), "e-changebg-prompt-snow");
+        assert_eq!(
+            encoded.transform().unwrap(),
+            "e-edit-prompte-YWRkIGEgaGF0LCByZWQ%3D"
+        );
+        assert_eq!(
+            fill.transform().unwrap(),
+            "w-800,h-400,cm-pad_resize,bg-genfill"
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_effects() {
+        assert!(Transformation::new()
+            .ai(AiEffect::Edit(" ".into()))
+            .transform()
+            .is_err());
+        assert!(Transformation::new()
+            .ai(AiEffect::DropShadow(DropShadow::new().elevation(95)))
+            .transform()
+            .is_err());
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod video_tests {
+    use super::test_utils::imagekit;
+    use super::url::{AudioCodec, Format, Options, Protocol, VideoCodec};
+    use super::Transformation;
+    use super::TransformationPosition;
+    use super::Url;
+
+    #[test]
+    fn transforms_video_parameters() {
+        let transformation = Transformation::new()
+            .width(640)
+            .format(Format::Webm)
+            .video_codec(VideoCodec::Vp9)
+            .audio_codec(AudioCodec::Opus)
+            .start_offset(2.5)
+            .end_offset(10.0)
+            .bitrate(1200);
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "w-640,f-webm,vc-vp9,ac-opus,so-2.5,eo-10,br-1200"
+        );
+        assert!(Transformation::new()
+            .start_offset(5.0)
+            .end_offset(2.0)
+            .transform()
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_non_finite_video_values() {
+        for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(Transformation::new()
+                .start_offset(value)
+                .transform()
+                .is_err());
+            assert!(Transformation::new().end_offset(value).transform().is_err());
+            assert!(Transformation::new().duration(value).transform().is_err());
+        }
+    }
+
+    #[test]
+    fn generates_thumbnail_url() {
+        let options = Options::new(Transformation::new().start_offset(5.0))
+            .path("videos/ferris.mp4")
+            .thumbnail();
+        let result = imagekit().url(options).unwrap();
+
+        assert_eq!(
+            result,
+            "https://ik.imagekit.io/demo/tr:so-5/videos/ferris.mp4/ik-thumbnail.jpg"
+        );
+
+        for position in [TransformationPosition::Path, TransformationPosition::Query] {
+            let options = Options::default()
+                .path("videos/ferris.mp4")
+                .transformation_position(position)
+                .thumbnail();
+
+            assert_eq!(
+                imagekit().url(options).unwrap(),
+                "https://ik.imagekit.io/demo/videos/ferris.mp4/ik-thumbnail.jpg"
+            );
+        }
+        assert!(imagekit()
+            .url(Options::default().path("videos/ferris.mp4"))
+            .is_err());
+    }
+
+    #[test]
+    fn generates_adaptive_streaming_urls() {
+        let hls = Options::new(Transformation::new())
+            .path("ferris.mp4")
+            .transformation_position(TransformationPosition::Query)
+            .adaptive_streaming(Protocol::Hls, vec![240, 360, 720]);
+        let dash = Options::default()
+            .path("ferris.mp4")
+            .adaptive_streaming(Protocol::Dash, vec![480]);
+
+        assert_eq!(
+            imagekit().url(hls).unwrap(),
+            "https://ik.imagekit.io/demo/ferris.mp4/ik-master.m3u8?tr=sr-240_360_720"
+        );
+        assert_eq!(
+            imagekit().url(dash).unwrap(),
+            "https://ik.imagekit.io/demo/tr:sr-480/ferris.mp4/ik-master.mpd"
+        );
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod named_tests {
+    use super::url::Options;
+    use super::ImageKit;
+    use super::Transformation;
+    use super::Url;
+
+    #[test]
+    fn transforms_named_and_raw_parameters() {
+        let transformation = Transformation::named("avatar")
+            .width(100)
+            .raw("e-grayscale", "")
+            .raw("ik-custom", "value");
+
+        assert_eq!(
+            transformation.transform().unwrap(),
+            "n-avatar,w-100,e-grayscale,ik-custom-value"
+        );
+    }
+
+    #[test]
+    fn chains_named_transformations() {
+        let imagekit =
+            ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap();
+        let options = Options::new(Transformation::named("avatar"))
+            .chain(Transformation::new().raw("rt", "90"))
+            .path("ferris.jpeg");
+
+        assert_eq!(
+            imagekit.url(options).unwrap(),
+            "https://ik.imagekit.io/demo/tr:n-avatar:rt-90/ferris.jpeg"
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_raw_parameters() {
+        assert!(Transformation::named("").transform().is_err());
+        assert!(Transformation::new().raw("w", "1,h-2").transform().is_err());
+        assert!(Transformation::new().raw("", "2").transform().is_err());
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod parse_tests {
+    use proptest::option;
+    use proptest::prelude::*;
+
+    use super::test_utils::imagekit;
+    use super::url::{
+        AiEffect, Crop, Focus, Format, ImageLayer, LayerPosition, Options, Rotation, Shadow,
+        TextLayer,
+    };
+    use super::Transformation;
+    use super::TransformationPosition;
+    use super::Url;
+
+    #[test]
+    fn parses_path_url() {
+        let (opts, transformations) = imagekit()
+            .parse_url("https://ik.imagekit.io/demo/tr:w-200,h-100:rt-90/images/ferris.jpeg")
+            .unwrap();
+
+        assert_eq!(
+            transformations,
+            vec![
+                Transformation::new().width(200).height(100),
+                Transformation::new().rotate(Rotation::Degrees(90)),
+            ]
+        );
+
+        let transformations = vec![Transformation::new().width(400)];
+        let url = imagekit()
+            .url(opts.transformations(transformations))
+            .unwrap();
+
+        assert_eq!(
+            url,
+            "https://ik.imagekit.io/demo/tr:w-400/images/ferris.jpeg"
+        );
+    }
+
+    #[test]
+    fn parses_query_and_src_urls() {
+        let (opts, transformations) = imagekit()
+            .parse_url("https://cdn.example.com/ferris.jpeg?tr=f-webp,ik-custom-1&v=2")
+            .unwrap();
+
+        assert_eq!(
+            transformations,
+            vec![Transformation::new()
+                .format(Format::Webp)
+                .raw("ik", "custom-1")]
+        );
+        assert_eq!(
+            imagekit()
+                .url(opts.transformations(transformations))
+                .unwrap(),
+            "https://cdn.example.com/ferris.jpeg?tr=f-webp,ik-custom-1&v=2"
+        );
+    }
+
+    #[test]
+    fn parses_nested_layers() {
+        let transformation = Transformation::new().layer(
+            ImageLayer::new("logos/white.png")
+                .transformation(Transformation::new().width(50).layer(TextLayer::new("Hi")))
+                .position(LayerPosition::new().x(-10)),
+        );
+        let serialized = transformation.transform().unwrap();
+
+        assert_eq!(
+            serialized.parse::<Transformation>().unwrap(),
+            transformation
+        );
+        assert!("l-image,i-logo.png".parse::<Transformation>().is_err());
+        assert!("w-100,l-end".parse::<Transformation>().is_err());
+        assert!("l-text,i-Hi,l-end,l-end".parse::<Transformation>().is_err());
+    }
+
+    fn crop() -> impl Strategy<Value = Crop> {
+        prop_oneof![
+            Just(Crop::MaintainRatio),
+            Just(Crop::Force),
+            Just(Crop::AtLeast),
+            Just(Crop::AtMax),
+        ]
+    }
+
+    fn focus() -> impl Strategy<Value = Focus> {
+        prop_oneof![
+            Just(Focus::Center),
+            Just(Focus::TopLeft),
+            Just(Focus::Face),
+            "[a-z]{1,8}".prop_map(Focus::Object),
+        ]
+    }
+
+    fn format() -> impl Strategy<Value = Format> {
+        prop_oneof![
+            Just(Format::Auto),
+            Just(Format::Avif),
+            Just(Format::Webp),
+            Just(Format::Png),
+        ]
+    }
+
+    fn transformation() -> impl Strategy<Value = Transformation> {
+        let resize = (
+            option::of(1u32..4000),
+            option::of(1u32..100),
+            option::of(crop()),
+            option::of(focus()),
+            option::of(1u32..1000),
+        );
+        let style = (
+            option::of(format()),
+            option::of(1u8..=100),
+            option::of(any::<bool>()),
+            any::<bool>(),
+            option::of(-360i32..=360),
+            option::of((0u32..=15, -100i32..=100)),
+        );
+        let overlays = (
+            option::of(("\\PC{1,16}", 1u32..200)),
+            option::of("[a-zA-Z ,.]{0,20}[a-z]"),
+            option::of(("[a-z]{1,8}", "[g-z][g-z \"%]{0,7}")),
+        );
+
+        (resize, style, overlays)
+            .prop_map(|(resize, style, overlays)| {
+                let (width, relative_height, crop, focus, zoom) = resize;
+                let (format, quality, progressive, grayscale, rotation, shadow) = style;
+                let (text, prompt, raw) = overlays;
+                let mut t = Transformation::new();
+
+                if let Some(width) = width {
+                    t = t.width(width);
+                }
+                if let Some(height) = relative_height {
+                    t = t.relative_height(height as f32 / 100.0);
+                }
+                if let Some(crop) = crop {
+                    t = t.crop(crop);
+                }
+                if let Some(focus) = focus {
+                    t = t.focus(focus);
+                }
+                if let Some(zoom) = zoom {
+                    t = t.zoom(zoom as f32 / 10.0);
+                }
+                if let Some(format) = format {
+                    t = t.format(format);
+                }
+                if let Some(quality) = quality {
+                    t = t.quality(quality);
+                }
+                if let Some(progressive) = progressive {
+                    t = t.progressive(progressive);
+                }
+                if grayscale {
+                    t = t.grayscale();
+                }
+                if let Some(rotation) = rotation {
+                    t = t.rotate(Rotation::Degrees(rotation));
+                }
+                if let Some((blur, offset_x)) = shadow {
+                    t = t.shadow(Shadow::new().blur(blur).offset_x(offset_x));
+                }
+                if let Some(prompt) = prompt {
+                    t = t.ai(AiEffect::Edit(prompt));
+                }
+                if let Some((key, value)) = raw {
+                    t = t.raw(format!("ik{key}"), value);
+                }
+                if let Some((text, font_size)) = text {
+                    t = t.layer(TextLayer::new(text).font_size(font_size));
+                }
+
+                t
+            })
+            .prop_filter("at least one parameter", |t| t.transform().is_ok())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn transformation_round_trips(transformation in transformation()) {
+            let serialized = transformation.transform().unwrap();
+
+            prop_assert_eq!(serialized.parse::<Transformation>().unwrap(), transformation);
+        }
+
+        #[test]
+        fn url_round_trips(
+            chain in prop::collection::vec(transformation(), 1..3),
+            path in "[a-zé ]{1,8}(/[a-z0-9_%?#]{1,8}){0,2}\\.jpg",
+            query_parameters in prop::collection::vec(
+                ("[a-z]{1,4}", "\\PC{0,8}"),
+                0..3,
+            ),
+            in_query in any::<bool>(),
+        ) {
+            let position = if in_query {
+                TransformationPosition::Query
+            } else {
+                TransformationPosition::Path
+            };
+            let reserved = query_parameters.iter().any(|(key, _)| key == "tr");
+            let opts = Options::default()
+                .transformations(chain.clone())
+                .path(path)
+                .query_parameters(query_parameters)
+                .transformation_position(position);
+
+            if reserved {
+                prop_assert!(imagekit().url(opts).is_err());
+                return Ok(());
+            }
+
+            let url = imagekit().url(opts).unwrap();
+            let (opts, parsed) = imagekit().parse_url(&url).unwrap();
+
+            prop_assert_eq!(&parsed, &chain);
+            prop_assert_eq!(imagekit().url(opts.transformations(parsed)).unwrap(), url);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod responsive_tests {
+    use super::test_utils::imagekit;
+    use super::url::{Breakpoints, Format, Options, Picture};
+    use super::Responsive;
+    use super::Transformation;
+    use super::TransformationPosition;
+
+    #[test]
+    fn generates_width_srcset() {
+        let options = Options::new(Transformation::new().quality(80))
+            .path("ferris.jpeg")
+            .transformation_position(TransformationPosition::Query);
+        let srcset = imagekit()
+            .srcset(options, &Breakpoints::Widths(vec![320, 640]))
+            .unwrap();
+
+        assert_eq!(
+            srcset,
+            "https://ik.imagekit.io/demo/ferris.jpeg?tr=w-320,q-80 320w, \
+            https://ik.imagekit.io/demo/ferris.jpeg?tr=w-640,q-80 640w"
+        );
+    }
+
+    #[test]
+    fn generates_dpr_srcset_on_last_step() {
+        let options = Options::new(Transformation::new().width(300))
+            .chain(Transformation::new().quality(80))
+            .path("ferris.jpeg");
+        let srcset = imagekit()
+            .srcset(options, &Breakpoints::Dpr(vec![1.0, 2.0]))
+            .unwrap();
+
+        assert_eq!(
+            srcset,
+            "https://ik.imagekit.io/demo/tr:w-300:dpr-1,q-80/ferris.jpeg 1x, \
+            https://ik.imagekit.io/demo/tr:w-300:dpr-2,q-80/ferris.jpeg 2x"
+        );
+        assert!(imagekit()
+            .srcset(
+                Options::default().path("ferris.jpeg"),
+                &Breakpoints::Widths(vec![])
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn generates_picture_markup() {
+        let picture = Picture::new(Breakpoints::Widths(vec![320, 640]))
+            .alt("Ferris \"the crab\"")
+            .sizes("50vw")
+            .formats(vec![Format::Webp]);
+        let html = imagekit()
+            .picture(Options::default().path("ferris.jpeg"), &picture)
+            .unwrap();
+
+        assert_eq!(
+            html,
+            "<picture>\
+            <source type=\"image/webp\" srcset=\"\
+            https://ik.imagekit.io/demo/tr:w-320,f-webp/ferris.jpeg 320w, \
+            https://ik.imagekit.io/demo/tr:w-640,f-webp/ferris.jpeg 640w\" sizes=\"50vw\">\
+            <img src=\"https://ik.imagekit.io/demo/tr:w-640/ferris.jpeg\" srcset=\"\
+            https://ik.imagekit.io/demo/tr:w-320/ferris.jpeg 320w, \
+            https://ik.imagekit.io/demo/tr:w-640/ferris.jpeg 640w\" sizes=\"50vw\" \
+            alt=\"Ferris &quot;the crab&quot;\" loading=\"lazy\">\
+            </picture>"
+        );
+        assert!(imagekit()
+            .picture(
+                Options::default().path("ferris.jpeg"),
+                &picture.formats(vec![Format::Orig])
+            )
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod webhook_tests {
+    use std::time::Duration;
+
+    use super::test_utils::now;
+    use super::webhook::{self, DEFAULT_TOLERANCE};
+    use super::WebhookEvent;
+
+    const SECRET: &str = "whsec_test";
+
+    const VIDEO_READY: &str = r#"{
+        "type": "video.transformation.ready",
+        "id": "9ef3e8e4-2b4f-4ca7-9e3b-7dcd6d9d2b2c",
+        "created_at": "2023-07-03T10:36:14.103Z",
+        "request": {
+            "url": "https://ik.imagekit.io/demo/sample.mp4?tr=f-webm",
+            "x_request_id": "ab5c6e64-3c5c-4d36-b2d6-2d3b19e5b0c4",
+            "user_agent": "curl/7.81.0"
+        },
+        "data": {
+            "asset": { "url": "https://ik.imagekit.io/demo/sample.mp4" },
+            "transformation": {
+                "type": "video-transformation",
+                "options": { "format": "webm" },
+                "output": {
+                    "url": "https://ik.imagekit.io/demo/sample.mp4?tr=f-webm",
+                    "video_metadata": {
+                        "duration": 6.2,
+                        "width": 1280,
+                        "height": 720,
+                        "bitrate": 1000000
+                    }
+                }
+            },
+            "timings": { "download_duration": 1200, "encoding_duration": 3400 }
+        }
+    }"#;
+
+    #[test]
+    fn verifies_signed_events() {
+        let signature = webhook::sign(VIDEO_READY, SECRET, now()).unwrap();
+        let event = webhook::verify(VIDEO_READY, &signature, SECRET, DEFAULT_TOLERANCE).unwrap();
+
+        let WebhookEvent::VideoTransformationReady(event) = event else {
+            panic!("expected video.transformation.ready, got {event:?}");
+        };
+        let output = event.data.transformation.output.unwrap();
+
+        assert_eq!(
+            event.request.x_request_id,
+            "ab5c6e64-3c5c-4d36-b2d6-2d3b19e5b0c4"
+        );
+        assert_eq!(
+            output.url,
+            "https://ik.imagekit.io/demo/sample.mp4?tr=f-webm"
+        );
+        assert_eq!(output.video_metadata.unwrap().width, 1280);
+    }
+
+    #[test]
+    fn signs_as_imagekit() {
+        assert_eq!(
+            webhook::sign("{}", "secret", 1655788406333).unwrap(),
+            "t=1655788406333,v1=\
+            b530c58787773c9d7b0cc141974ee7fc5dfde5086cb7c45fe7b134ad3fcf378d"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let timestamp = now();
+        let signature = webhook::sign(VIDEO_READY, SECRET, timestamp).unwrap();
+        let tampered = VIDEO_READY.replace("1280", "1920");
+
+        assert!(webhook::verify(&tampered, &signature, SECRET, DEFAULT_TOLERANCE).is_err());
+        assert!(
+            webhook::verify(VIDEO_READY, &signature, "whsec_other", DEFAULT_TOLERANCE).is_err()
+        );
+        assert!(webhook::verify(VIDEO_READY, "v1=00", SECRET, DEFAULT_TOLERANCE).is_err());
+
+        let expired = webhook::sign(VIDEO_READY, SECRET, timestamp - 600_000).unwrap();
+
+        assert!(webhook::verify(VIDEO_READY, &expired, SECRET, Duration::from_secs(300)).is_err());
+    }
+
+    #[test]
+    fn verifies_at_the_provided_time() {
+        let timestamp = 1_700_000_000_000;
+        let signature = webhook::sign(VIDEO_READY, SECRET, timestamp).unwrap();
+        let verify_at =
+            |now| webhook::verify_at(VIDEO_READY, &signature, SECRET, DEFAULT_TOLERANCE, now);
+
+        assert!(verify_at(timestamp + 299_000).is_ok());
+        assert!(verify_at(timestamp - 299_000).is_ok());
+        assert!(verify_at(timestamp + 301_000).is_err());
+        assert!(webhook::verify(VIDEO_READY, &signature, SECRET, DEFAULT_TOLERANCE).is_err());
+    }
+
+    #[test]
+    fn deserializes_upload_and_unknown_events() {
+        let body = r#"{
+            "type": "upload.post-transform.error",
+            "id": "1",
+            "created_at": "2023-07-03T10:36:14.103Z",
+            "request": {
+                "x_request_id": "2",
+                "transformation": { "type": "transformation", "value": "w-100" }
+            },
+            "data": {
+                "fileId": "3",
+                "name": "ferris.jpeg",
+                "path": "/ferris.jpeg",
+                "url": "https://ik.imagekit.io/demo/ferris.jpeg",
+                "transformation": { "error": { "reason": "encoding_failed" } }
+            }
+        }"#;
+        let signature = webhook::sign(body, SECRET, now()).unwrap();
+        let event = webhook::verify(body, &signature, SECRET, DEFAULT_TOLERANCE).unwrap();
+
+        let WebhookEvent::UploadPostTransformError(event) = event else {
+            panic!("expected upload.post-transform.error, got {event:?}");
+        };
+
+        assert_eq!(event.data.file_id.as_deref(), Some("3"));
+        assert_eq!(event.data.transformation.error.reason, "encoding_failed");
+
+        let body = r#"{ "type": "extension.completed", "id": "1" }"#;
+        let signature = webhook::sign(body, SECRET, now()).unwrap();
+
+        assert!(matches!(
+            webhook::verify(body, &signature, SECRET, DEFAULT_TOLERANCE).unwrap(),
+            WebhookEvent::Unknown
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "webhooks-axum"))]
+mod webhook_axum_tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::bail;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use super::test_utils::now;
+    use super::webhook::axum::router;
+    use super::webhook::{self, DEFAULT_TOLERANCE, SIGNATURE_HEADER};
+    use super::WebhookEvent;
+
+    const SECRET: &str = "whsec_test";
+
+    const BODY: &str = r#"{
+        "type": "video.transformation.accepted",
+        "id": "1",
+        "created_at": "2023-07-03T10:36:14.103Z",
+        "request": { "x_request_id": "2", "url": "https://ik.imagekit.io/demo/sample.mp4" },
+        "data": {
+            "asset": { "url": "https://ik.imagekit.io/demo/sample.mp4" },
+            "transformation": { "type": "video-transformation" }
+        }
+    }"#;
+
+    fn app(events: Arc<Mutex<Vec<String>>>) -> Router {
+        router(SECRET, DEFAULT_TOLERANCE, move |event: WebhookEvent| {
+            let events = events.clone();
+
+            async move {
+                match event {
+                    WebhookEvent::VideoTransformationAccepted(event) => {
+                        events.lock().unwrap().push(event.id);
+                        Ok(())
+                    }
+                    event => bail!("Unexpected event {event:?}"),
+                }
+            }
+        })
+    }
+
+    async fn send(app: Router, body: &str, signature: Option<String>) -> StatusCode {
+        let mut request = Request::post("/");
+
+        if let Some(signature) = signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        app.oneshot(request.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn dispatches_verified_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let signature = webhook::sign(BODY, SECRET, now()).unwrap();
+
+        assert_eq!(
+            send(app(events.clone()), BODY, Some(signature)).await,
+            StatusCode::OK
+        );
+        assert_eq!(*events.lock().unwrap(), vec!["1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn rejects_unsigned_and_replayed_requests() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let replayed = webhook::sign(BODY, SECRET, now() - 3_600_000).unwrap();
+        let forged = webhook::sign(BODY, "whsec_other", now()).unwrap();
+
+        assert_eq!(
+            send(app(events.clone()), BODY, None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            send(app(events.clone()), BODY, Some(replayed)).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            send(app(events.clone()), BODY, Some(forged)).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_invalid_bodies_and_handler_errors() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let invalid = "{ \"type\": \"video.transformation.accepted\" }";
+        let unknown = r#"{ "type": "extension.completed" }"#;
+
+        assert_eq!(
+            send(
+                app(events.clone()),
+                invalid,
+                Some(webhook::sign(invalid, SECRET, now()).unwrap())
+            )
+            .await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            send(
+                app(events.clone()),
+                unknown,
+                Some(webhook::sign(unknown, SECRET, now()).unwrap())
+            )
+            .await,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
+
+#[cfg(test)]
+mod usage_tests {
+    use chrono::NaiveDate;
+
+    use super::accounts::usage::split_range;
+    use super::accounts::usage::AccountUsage;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn keeps_short_ranges() {
+        assert_eq!(
+            split_range(date(2023, 1, 1), date(2023, 2, 1)).unwrap(),
+            vec![(date(2023, 1, 1), date(2023, 2, 1))]
+        );
+    }
+
+    #[test]
+    fn splits_long_ranges() {
+        assert_eq!(
+            split_range(date(2023, 1, 1), date(2024, 1, 1)).unwrap(),
+            vec![
+                (date(2023, 1, 1), date(2023, 3, 31)),
+                (date(2023, 3, 31), date(2023, 6, 28)),
+                (date(2023, 6, 28), date(2023, 9, 25)),
+                (date(2023, 9, 25), date(2023, 12, 23)),
+                (date(2023, 12, 23), date(2024, 1, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_empty_ranges() {
+        assert!(split_range(date(2023, 1, 1), date(2023, 1, 1)).is_err());
+        assert!(split_range(date(2023, 2, 1), date(2023, 1, 1)).is_err());
+    }
+
+    #[test]
+    fn merges_consecutive_periods() {
+        let earlier = AccountUsage {
+            bandwidth_bytes: 100,
+            media_library_storage_bytes: 1000,
+            video_processing_units_count: 2,
+            extension_units_count: 1,
+            original_cache_storage_bytes: 50,
+        };
+        let later = AccountUsage {
+            bandwidth_bytes: 200,
+            media_library_storage_bytes: 1500,
+            video_processing_units_count: 3,
+            extension_units_count: 4,
+            original_cache_storage_bytes: 80,
+        };
+
+        assert_eq!(
+            earlier.merge(later),
+            AccountUsage {
+                bandwidth_bytes: 300,
+                media_library_storage_bytes: 1500,
+                video_processing_units_count: 5,
+                extension_units_count: 5,
+                original_cache_storage_bytes: 80,
+            }
+        );
+    }
+
+    #[test]
+    fn defaults_missing_counters() {
+        let usage: AccountUsage = serde_json::from_str(r#"{"bandwidthBytes": 100}"#).unwrap();
+
+        assert_eq!(
+            usage,
+            AccountUsage {
+                bandwidth_bytes: 100,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<AccountUsage>("{}").unwrap(),
+            AccountUsage::default()
+        );
+    }
+}
+
+#[cfg(test)]
+mod accounts_tests {
+    use serde_json::json;
+
+    use super::accounts::origins::{S3Origin, WebFolderOrigin};
+    use super::accounts::{Origin, OriginConfig, UrlEndpoint, UrlEndpointConfig, UrlRewriter};
+    use super::types::Secret;
+
+    #[test]
+    fn serializes_tagged_origins() {
+        let config = OriginConfig::S3(S3Origin {
+            name: "products".to_string(),
+            bucket: "acme-products".to_string(),
+            access_key: "AKIA".to_string(),
+            secret_key: Some(Secret::new("s3cr3t")),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            json!({
+                "type": "S3",
+                "name": "products",
+                "bucket": "acme-products",
+                "accessKey": "AKIA",
+                "secretKey": "s3cr3t",
+                "includeCanonicalHeader": false
+            })
+        );
+        assert!(!format!("{config:?}").contains("s3cr3t"));
+    }
+
+    #[test]
+    fn deserializes_origins_without_secrets() {
+        let origin: Origin = serde_json::from_value(json!({
+            "id": "6409bbdb9b7bf2a8d0e7f2a1",
+            "type": "WEB_FOLDER",
+            "name": "assets",
+            "baseUrl": "https://assets.example.com",
+            "forwardHostHeaderToOrigin": true,
+            "includeCanonicalHeader": false
+        }))
+        .unwrap();
+
+        assert_eq!(origin.id, "6409bbdb9b7bf2a8d0e7f2a1");
+        assert_eq!(
+            origin.config,
+            OriginConfig::WebFolder(WebFolderOrigin {
+                name: "assets".to_string(),
+                base_url: "https://assets.example.com".to_string(),
+                forward_host_header_to_origin: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn serializes_url_endpoints() {
+        let config = UrlEndpointConfig {
+            description: "Products".to_string(),
+            url_prefix: Some("products".to_string()),
+            origins: vec!["6409bbdb9b7bf2a8d0e7f2a1".to_string()],
+            url_rewriter: Some(UrlRewriter::Cloudinary {
+                preserve_asset_delivery_types: true,
+            }),
+        };
+        let value = serde_json::to_value(&config).unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "description": "Products",
+                "urlPrefix": "products",
+                "origins": ["6409bbdb9b7bf2a8d0e7f2a1"],
+                "urlRewriter": { "type": "CLOUDINARY", "preserveAssetDeliveryTypes": true }
+            })
+        );
+
+        let mut value = value;
+        value["id"] = json!("6409bc1f9b7bf2a8d0e7f2a2");
+        let endpoint: UrlEndpoint = serde_json::from_value(value).unwrap();
+
+        assert_eq!(endpoint.config, config);
+    }
+}
+
+#[cfg(test)]
+mod client_tests {
+    use super::client::{check_response, check_success, parse_response, parse_success};
+    use super::test_utils::response;
+    use super::upload::types::Response;
+
+    #[test]
+    fn returns_error_response_messages() {
+        let body = r#"{ "message": "Your request contains invalid fileId parameter." }"#;
+        let error = parse_response::<Response>(&response(400, body), 200).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Your request contains invalid fileId parameter."
+        );
+        assert!(check_response(&response(204, ""), 204).is_ok());
+        assert!(check_response(&response(404, ""), 204).is_err());
+    }
+
+    #[test]
+    fn accepts_any_success_status() {
+        let body = r#"{ "message": "Not Found" }"#;
+
+        assert!(check_success(&response(200, "")).is_ok());
+        assert!(check_success(&response(204, "")).is_ok());
+        assert_eq!(
+            check_success(&response(404, body)).unwrap_err().to_string(),
+            "Not Found"
+        );
+        assert_eq!(
+            parse_success::<serde_json::Value>(&response(201, "[]")).unwrap(),
+            serde_json::json!([])
+        );
+    }
+}
+
+#[cfg(test)]
+mod transport_tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+
+    use super::accounts::origins::WebFolderOrigin;
+    use super::accounts::OriginConfig;
+    use super::file_details::Details;
+    use super::http::{HttpRequest, HttpResponse, HttpTransport, Method};
+    use super::test_utils::response;
+    use super::upload::authentication::{signature, validate_expire, MAX_EXPIRE};
+    use super::upload::{AuthenticationParameters, Options, UploadFile};
+    use super::{Delete, ImageKit, Origins, Upload, UrlEndpoints};
+
+    /// Records the requests and answers them with the queued responses
+    #[derive(Clone, Default)]
+    struct RecordingTransport {
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+        responses: Arc<Mutex<Vec<HttpResponse>>>,
+    }
+
+    #[async_trait]
+    impl HttpTransport for RecordingTransport {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+            self.requests.lock().unwrap().push(request);
+
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_requests_through_custom_transports() {
+        let transport = RecordingTransport::default();
+        let imagekit = ImageKit::with_transport(
+            "public_key",
+            "private_key",
+            "https://ik.imagekit.io/demo",
+            transport.clone(),
+        );
+
+        transport.responses.lock().unwrap().extend([
+            response(
+                200,
+                r#"{
+                    "fileId": "6409bbdb9b7bf2a8d0e7f2a1",
+                    "name": "ferris.jpeg",
+                    "size": 2,
+                    "versionInfo": { "id": "6409bbdb9b7bf2a8d0e7f2a1", "name": "Version 1" },
+                    "filePath": "/ferris.jpeg",
+                    "url": "https://ik.imagekit.io/demo/ferris.jpeg",
+                    "fileType": "image"
+                }"#,
+            ),
+            response(204, ""),
+        ]);
+
+        let upload = imagekit
+            .upload(Options::new(
+                UploadFile::Bytes(vec![0xFF, 0xD8]),
+                "ferris.jpeg",
+            ))
+            .await
+            .unwrap();
+
+        imagekit.delete(&upload.file_id).await.unwrap();
+
+        let requests = transport.requests.lock().unwrap();
+        let content_type = requests[0]
+            .headers
+            .iter()
+            .find(|(key, _)| key == "Content-Type")
+            .map(|(_, value)| value.clone())
+            .unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+
+        assert_eq!(requests[0].method, Method::Post);
+        assert_eq!(
+            requests[0].url,
+            "https://upload.imagekit.io/api/v1/files/upload"
+        );
+        assert!(requests[0].headers.contains(&(
+            "Authorization".to_string(),
+            "Basic cHJpdmF0ZV9rZXk6".to_string()
+        )));
+        assert_eq!(
+            requests[0].body.as_bytes().unwrap(),
+            [
+                format!(
+                    "--{boundary}\r\n\
+                    Content-Disposition: form-data; name=\"fileName\"\r\n\r\n\
+                    ferris.jpeg\r\n\
+                    --{boundary}\r\n\
+                    Content-Disposition: form-data; name=\"file\"; filename=\"ferris.jpeg\"\r\n\
+                    Content-Type: image/jpeg\r\n\r\n"
+                )
+                .into_bytes(),
+                vec![0xFF, 0xD8],
+                format!("\r\n--{boundary}--\r\n").into_bytes(),
+            ]
+            .concat()
+        );
+        assert_eq!(requests[1].method, Method::Delete);
+        assert_eq!(
+            requests[1].url,
+            "https://api.imagekit.io/v1/files/6409bbdb9b7bf2a8d0e7f2a1"
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn streams_uploaded_files() {
+        let transport = RecordingTransport::default();
+        let imagekit = ImageKit::with_transport(
+            "public_key",
+            "private_key",
+            "https://ik.imagekit.io/demo",
+            transport.clone(),
+        );
+        let data = std::fs::read("assets/ferris.jpeg").unwrap();
+        let file = tokio::fs::File::open("assets/ferris.jpeg").await.unwrap();
+
+        transport
+            .responses
+            .lock()
+            .unwrap()
+            .push(response(500, r#"{ "message": "Internal error" }"#));
+
+        assert!(imagekit
+            .upload(Options::new(UploadFile::from(file), "ferris.jpeg"))
+            .await
+            .is_err());
+
+        let mut request = transport.requests.lock().unwrap().remove(0);
+        let length = request
+            .headers
+            .iter()
+            .find(|(key, _)| key == "Content-Length")
+            .map(|(_, value)| value.parse::<u64>().unwrap());
+
+        assert!(request.body.as_bytes().is_none());
+        assert_eq!(length, Some(request.body.len()));
+        assert!(format!("{request:?}").contains("[stream of"));
+
+        let body = request.take_body().collect().await.unwrap();
+
+        assert_eq!(body.len() as u64, length.unwrap());
+        assert!(body.windows(data.len()).any(|window| window == data));
+    }
+
+    #[tokio::test]
+    async fn accepts_any_account_management_success_status() {
+        let transport = RecordingTransport::default();
+        let imagekit = ImageKit::with_transport(
+            "public_key",
+            "private_key",
+            "https://ik.imagekit.io/demo",
+            transport.clone(),
+        );
+        let config = OriginConfig::WebFolder(WebFolderOrigin {
+            name: "assets".to_string(),
+            base_url: "https://assets.example.com".to_string(),
+            ..Default::default()
+        });
+        let origin = r#"{
+            "id": "6409bbdb9b7bf2a8d0e7f2a1",
+            "type": "WEB_FOLDER",
+            "name": "assets",
+            "baseUrl": "https://assets.example.com"
+        }"#;
+
+        transport.responses.lock().unwrap().extend([
+            response(201, origin),
+            response(200, origin),
+            response(204, ""),
+            response(200, ""),
+            response(400, r#"{ "message": "Invalid origin" }"#),
+        ]);
+
+        let created = imagekit.create_origin(&config).await.unwrap();
+
+        assert_eq!(created.id, "6409bbdb9b7bf2a8d0e7f2a1");
+        assert!(imagekit.create_origin(&config).await.is_ok());
+        assert!(imagekit.delete_origin(&created.id).await.is_ok());
+        assert!(imagekit.delete_origin(&created.id).await.is_ok());
+        assert_eq!(
+            imagekit
+                .create_origin(&config)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "Invalid origin"
+        );
+    }
+
+    #[tokio::test]
+    async fn escapes_account_ids() {
+        let transport = RecordingTransport::default();
+        let imagekit = ImageKit::with_transport(
+            "public_key",
+            "private_key",
+            "https://ik.imagekit.io/demo",
+            transport.clone(),
+        );
+
+        transport
+            .responses
+            .lock()
+            .unwrap()
+            .extend([response(204, ""), response(204, "")]);
+
+        imagekit.delete_origin("../files/a b").await.unwrap();
+        imagekit.delete_url_endpoint("id?x=1#y").await.unwrap();
+
+        let requests = transport.requests.lock().unwrap();
+
+        assert_eq!(
+            requests[0].url,
+            "https://api.imagekit.io/v1/accounts/origins/..%2Ffiles%2Fa%20b"
+        );
+        assert_eq!(
+            requests[1].url,
+            "https://api.imagekit.io/v1/accounts/url-endpoints/id%3Fx%3D1%23y"
+        );
+    }
+
+    #[tokio::test]
+    async fn escapes_file_ids() {
+        let transport = RecordingTransport::default();
+        let imagekit = ImageKit::with_transport(
+            "public_key",
+            "private_key",
+            "https://ik.imagekit.io/demo",
+            transport.clone(),
+        );
+        let not_found = r#"{ "message": "The requested file does not exist." }"#;
+
+        transport
+            .responses
+            .lock()
+            .unwrap()
+            .extend([response(204, ""), response(404, not_found)]);
+
+        imagekit.delete("../accounts/a b").await.unwrap();
+        assert!(imagekit.get_file_details("id?x=1#y").await.is_err());
+
+        let requests = transport.requests.lock().unwrap();
+
+        assert_eq!(
+            requests[0].url,
+            "https://api.imagekit.io/v1/files/..%2Faccounts%2Fa%20b"
+        );
+        assert_eq!(
+            requests[1].url,
+            "https://api.imagekit.io/v1/files/id%3Fx%3D1%23y/details"
+        );
+    }
+
+    #[test]
+    fn mints_authentication_parameters() {
+        let imagekit = ImageKit::with_transport(
+            "public_key",
+            "private_key",
+            "https://ik.imagekit.io/demo",
+            RecordingTransport::default(),
+        );
+        let params = imagekit.generate_authentication_parameters().unwrap();
+
+        assert_eq!(
+            signature("private_key", "unique_token", 1700000000).unwrap(),
+            "ce549f24091317a545aa94c8eaca5a14aa6b2940"
+        );
+        assert_eq!(
+            params.signature,
+            signature("private_key", &params.token, params.expire).unwrap()
+        );
+        assert_ne!(
+            imagekit.generate_authentication_parameters().unwrap().token,
+            imagekit.generate_authentication_parameters().unwrap().token
+        );
+
+        let client = ImageKit::public_with_transport(
+            "public_key",
+            "https://ik.imagekit.io/demo",
+            RecordingTransport::default(),
+        );
+
+        assert!(client
+            .authentication_parameters("unique_token", params.expire)
+            .is_err());
+    }
+
+    #[test]
+    fn validates_expire() {
+        let now = 1_700_000_000;
+
+        assert!(validate_expire(now + 1, now).is_ok());
+        assert!(validate_expire(now + MAX_EXPIRE, now).is_ok());
+        assert!(validate_expire(now, now).is_err());
+        assert!(validate_expire(now - 60, now).is_err());
+        assert!(validate_expire(now + MAX_EXPIRE + 1, now).is_err());
+
+        let imagekit = ImageKit::with_transport(
+            "public_key",
+            "private_key",
+            "https://ik.imagekit.io/demo",
+            RecordingTransport::default(),
+        );
+
+        assert!(imagekit.authentication_parameters("token", now).is_err());
+    }
+
+    #[tokio::test]
+    async fn uploads_with_authentication_parameters() {
+        let transport = RecordingTransport::default();
+        let client = ImageKit::public_with_transport(
+            "public_key",
+            "https://ik.imagekit.io/demo",
+            transport.clone(),
+        );
+        let params = AuthenticationParameters {
+            token: "unique_token".to_string(),
+            expire: 1700000000,
+            signature: "ce549f24091317a545aa94c8eaca5a14aa6b2940".to_string(),
+        };
+
+        transport.responses.lock().unwrap().push(response(
+            403,
+            r#"{ "message": "Your request has expired." }"#,
+        ));
+
+        let error = client
+            .upload(
+                Options::new(UploadFile::Bytes(vec![0xFF, 0xD8]), "ferris.jpeg")
+                    .authentication_parameters(params),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(client
+            .upload(Options::new(UploadFile::Bytes(vec![]), "ferris.jpeg"))
+            .await
+            .is_err());
+
+        let requests = transport.requests.lock().unwrap();
+        let body = String::from_utf8_lossy(requests[0].body.as_bytes().unwrap());
+
+        assert_eq!(error.to_string(), "Your request has expired.");
+        assert!(requests[0]
+            .headers
+            .iter()
+            .all(|(key, _)| key != "Authorization"));
+
+        for (name, value) in [
+            ("publicKey", "public_key"),
+            ("token", "unique_token"),
+            ("expire", "1700000000"),
+            ("signature", "ce549f24091317a545aa94c8eaca5a14aa6b2940"),
+        ] {
+            assert!(body.contains(&format!("name=\"{name}\"\r\n\r\n{value}\r\n")));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod blocking_tests {
+    use std::io::{Seek, SeekFrom};
+    use std::sync::{Arc, Mutex};
+
+    use tokio::runtime::Runtime;
+
+    use super::blocking::{Delete, Details, ImageKit, Options, Upload, UploadFile};
+    use super::metrics::{ErrorKind, MetricsObserver, Operation, RequestMetrics};
+    use super::mock_server::{MockServer, URL_ENDPOINT};
+    use super::url::{Breakpoints, Options as UrlOptions};
+    use super::{Responsive, Transformation, Url};
+
+    fn imagekit() -> ImageKit {
+        ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap()
+    }
+
+    #[test]
+    fn generates_urls_as_the_async_client() {
+        let options = || UrlOptions::new(Transformation::new().width(300)).path("ferris.jpeg");
+        let async_client =
+            super::ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo")
+                .unwrap();
+
+        assert_eq!(
+            imagekit().url(options()).unwrap(),
+            async_client.url(options()).unwrap()
+        );
+        assert_eq!(
+            imagekit()
+                .srcset(options(), &Breakpoints::Widths(vec![320]))
+                .unwrap(),
+            "https://ik.imagekit.io/demo/tr:w-320/ferris.jpeg 320w"
+        );
+
+        let (opts, transformations) = imagekit()
+            .parse_url("https://ik.imagekit.io/demo/tr:w-300/ferris.jpeg")
+            .unwrap();
+
+        assert_eq!(transformations, vec![Transformation::new().width(300)]);
+        assert_eq!(
+            imagekit()
+                .url(opts.transformations(transformations))
+                .unwrap(),
+            "https://ik.imagekit.io/demo/tr:w-300/ferris.jpeg"
+        );
+    }
+
+    #[test]
+    fn reports_upload_errors() {
+        let options = Options::new(vec![0xFF, 0xD8].into(), "ferris.jpeg")
+            .endpoint("http://127.0.0.1:9/api/v1/files/upload");
+
+        assert!(imagekit().upload(options).is_err());
+    }
+
+    /// Starts a `MockServer` on its own runtime, as the blocking client must
+    /// not be used within one
+    fn mock_server() -> (Runtime, MockServer) {
+        let runtime = Runtime::new().unwrap();
+        let server = runtime.block_on(MockServer::start()).unwrap();
+
+        (runtime, server)
+    }
+
+    #[test]
+    fn uploads_retrieves_and_deletes_files() {
+        let (_runtime, server) = mock_server();
+        let imagekit = server.blocking_client().unwrap();
+        let file = std::fs::File::open("assets/ferris.jpeg").unwrap();
+
+        let uploaded = imagekit
+            .upload(Options::new(UploadFile::from(file), "ferris"))
+            .unwrap();
+
+        assert_eq!(uploaded.width, Some(640));
+        assert_eq!(uploaded.height, Some(640));
+
+        let details = imagekit.get_file_details(&uploaded.file_id).unwrap();
+
+        assert_eq!(details.file_id, uploaded.file_id);
+        assert!(imagekit.delete(&uploaded.file_id).is_ok());
+        assert!(server.files().is_empty());
+
+        let error = imagekit.get_file_details(&uploaded.file_id).unwrap_err();
+
+        assert_eq!(error.to_string(), "The requested file does not exist.");
+    }
+
+    #[test]
+    fn streams_files_from_their_position() {
+        let (_runtime, server) = mock_server();
+        let imagekit = server.blocking_client().unwrap();
+        let size = std::fs::metadata("assets/ferris.jpeg").unwrap().len();
+        let mut file = std::fs::File::open("assets/ferris.jpeg").unwrap();
+
+        file.seek(SeekFrom::Start(2)).unwrap();
+
+        let uploaded = imagekit
+            .upload(Options::new(UploadFile::from(file), "ferris"))
+            .unwrap();
+
+        assert_eq!(uploaded.size, size - 2);
+        assert_eq!(uploaded.width, None);
+    }
+
+    #[test]
+    fn uploads_with_authentication_parameters() {
+        let (_runtime, server) = mock_server();
+        let params = server
+            .client()
+            .unwrap()
+            .generate_authentication_parameters()
+            .unwrap();
+        let imagekit = ImageKit::public("public_key", URL_ENDPOINT)
+            .unwrap()
+            .api_endpoint(server.url())
+            .upload_endpoint(server.upload_endpoint());
+        let ferris = || UploadFile::from(std::fs::read("assets/ferris.jpeg").unwrap());
+
+        assert!(imagekit.upload(Options::new(ferris(), "ferris")).is_err());
+        assert!(imagekit
+            .upload(Options::new(ferris(), "ferris").authentication_parameters(params))
+            .is_ok());
+        assert_eq!(server.files().len(), 1);
+        assert!(imagekit.delete(&server.files()[0].file_id).is_err());
+        assert_eq!(server.files().len(), 1);
+    }
+
+    #[derive(Clone, Default)]
+    struct RecordingObserver {
+        requests: Arc<Mutex<Vec<RequestMetrics>>>,
+    }
+
+    impl MetricsObserver for RecordingObserver {
+        fn on_request(&self, metrics: &RequestMetrics) {
+            self.requests.lock().unwrap().push(metrics.clone());
+        }
+    }
+
+    #[test]
+    fn observes_every_request() {
+        let (_runtime, server) = mock_server();
+        let observer = RecordingObserver::default();
+        let imagekit = server
+            .blocking_client()
+            .unwrap()
+            .metrics_observer(observer.clone());
+        let data = std::fs::read("assets/ferris.jpeg").unwrap();
+        let size = data.len() as u64;
+        let uploaded = imagekit
+            .upload(Options::new(UploadFile::from(data), "ferris"))
+            .unwrap();
+
+        imagekit.get_file_details(&uploaded.file_id).unwrap();
+        imagekit.delete(&uploaded.file_id).unwrap();
+        assert!(imagekit.delete(&uploaded.file_id).is_err());
+
+        let requests = observer.requests.lock().unwrap();
+        let outcomes: Vec<(Operation, Option<u16>, Option<ErrorKind>)> = requests
+            .iter()
+            .map(|metrics| (metrics.operation, metrics.status, metrics.error))
+            .collect();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                (Operation::Upload, Some(200), None),
+                (Operation::GetFileDetails, Some(200), None),
+                (Operation::Delete, Some(204), None),
+                (Operation::Delete, Some(404), Some(ErrorKind::NotFound)),
+            ]
+        );
+        assert!(requests[0].bytes_sent > size);
+        assert_eq!(requests[1].bytes_sent, 0);
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod wasm_tests {
+    use super::url::{Breakpoints, Options};
+    use super::{ImageKit, Responsive, Transformation, Url, UrlGenerator};
+
+    #[test]
+    fn generates_urls_without_credentials() {
+        let generator = UrlGenerator::new("https://ik.imagekit.io/demo");
+        let imagekit =
+            ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap();
+        let options = || Options::new(Transformation::new().width(300)).path("ferris.jpeg");
+
+        assert_eq!(
+            generator.url(options()).unwrap(),
+            imagekit.url(options()).unwrap()
+        );
+        assert_eq!(
+            generator
+                .srcset(options(), &Breakpoints::Dpr(vec![2.0]))
+                .unwrap(),
+            "https://ik.imagekit.io/demo/tr:w-300,dpr-2/ferris.jpeg 2x"
+        );
+        assert_eq!(
+            generator
+                .parse_url("https://ik.imagekit.io/demo/tr:w-300/ferris.jpeg")
+                .unwrap()
+                .1,
+            vec![Transformation::new().width(300)]
+        );
+    }
+}
+
+#[cfg(test)]
+mod phash_tests {
+    use super::phash::{group_duplicates, PHash, DEFAULT_THRESHOLD};
+
+    #[test]
+    fn parses_and_displays_phash() {
+        let phash: PHash = "63433b3ccf8e1ebe".parse().unwrap();
+
+        assert_eq!(phash.value(), 0x63433b3ccf8e1ebe);
+        assert_eq!(phash.to_string(), "63433b3ccf8e1ebe");
+        assert!("63433b3ccf8e1eb".parse::<PHash>().is_err());
+        assert!("63433b3ccf8e1ebg".parse::<PHash>().is_err());
+    }
+
+    #[test]
+    fn computes_hamming_distance() {
+        let a = PHash::new(0);
+        let b = PHash::new(0b1011);
+
+        assert_eq!(a.distance(&b), 3);
+        assert_eq!(a.distance(&PHash::new(u64::MAX)), 64);
+        assert!(a.is_similar(&b, 3));
+        assert!(!a.is_similar(&b, 2));
+        assert_eq!(a.similarity(&a), 1.0);
+    }
+
+    #[test]
+    fn groups_near_duplicates() {
+        let hashes = vec![
+            PHash::new(0),
+            PHash::new(u64::MAX),
+            PHash::new(0b11),
+            PHash::new(u64::MAX - 1),
+            PHash::new(0x00ff_00ff_00ff_00ff),
+        ];
+        let clusters = group_duplicates(&hashes, DEFAULT_THRESHOLD);
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0], vec![&hashes[0], &hashes[2]]);
+        assert_eq!(clusters[1], vec![&hashes[1], &hashes[3]]);
+    }
+}
+
+#[cfg(test)]
+mod testing_tests {
+    use super::delete::Delete;
+    use super::file_details::Details;
+    use super::testing::FakeImageKit;
+    use super::upload::types::FileType;
+    use super::upload::{Options, Upload, UploadFile};
+
+    fn fake() -> FakeImageKit {
+        FakeImageKit::new("https://ik.imagekit.io/demo/")
+    }
+
+    fn asset(path: &str) -> UploadFile {
+        UploadFile::from(std::fs::read(path).unwrap())
+    }
+
+    #[tokio::test]
+    async fn uploads_files_with_imagekit_like_fields() {
+        let imagekit = fake();
+        let uploaded = imagekit
+            .upload(Options::new(asset("assets/ferris.jpeg"), "ferris.jpeg"))
+            .await
+            .unwrap();
+
+        assert_eq!(uploaded.file_id.len(), 24);
+        assert!(uploaded.file_id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(uploaded.version_info.id, uploaded.file_id);
+        assert_eq!(uploaded.version_info.name, "Version 1");
+        assert!(uploaded.name.starts_with("ferris_"));
+        assert!(uploaded.name.ends_with(".jpeg"));
+        assert_eq!(uploaded.name.len(), "ferris_.jpeg".len() + 9);
+        assert_eq!(uploaded.file_path, format!("/{}", uploaded.name));
+        assert_eq!(
+            uploaded.url,
+            format!("https://ik.imagekit.io/demo/{}", uploaded.name)
+        );
+        assert_eq!(
+            uploaded.thumbnail_url.unwrap(),
+            format!(
+                "https://ik.imagekit.io/demo/tr:n-ik_ml_thumbnail/{}",
+                uploaded.name
+            )
+        );
+        assert_eq!(
+            uploaded.size,
+            std::fs::metadata("assets/ferris.jpeg").unwrap().len()
+        );
+    }
+
+    #[tokio::test]
+    async fn gives_each_upload_a_unique_id_and_name() {
+        let imagekit = fake();
+        let first = imagekit
+            .upload(Options::new(asset("assets/ferris.jpeg"), "ferris"))
+            .await
+            .unwrap();
+        let second = imagekit
+            .upload(Options::new(asset("assets/ferris.jpeg"), "ferris"))
+            .await
+            .unwrap();
+        let files = imagekit.files();
+
+        assert_ne!(first.file_id, second.file_id);
+        assert_ne!(first.name, second.name);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].file_id, first.file_id);
+        assert_eq!(files[1].file_id, second.file_id);
+    }
+
+    #[tokio::test]
+    async fn reads_dimensions_of_png_images() {
+        let uploaded = fake()
+            .upload(Options::new(asset("assets/imagekit.png"), "imagekit.png"))
+            .await
+            .unwrap();
+
+        assert_eq!(uploaded.file_type, FileType::Image);
+        assert!(uploaded.width.unwrap() > 0);
+        assert!(uploaded.height.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn uploads_non_image_files() {
+        let uploaded = fake()
+            .upload(Options::new(asset("assets/imagekit.svg"), "imagekit.svg"))
+            .await
+            .unwrap();
+
+        assert_eq!(uploaded.file_type, FileType::NonImage);
+        assert!(uploaded.width.is_none());
+        assert!(uploaded.height.is_none());
+        assert!(uploaded.thumbnail_url.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_uploads() {
+        let imagekit = fake();
+        let empty = imagekit
+            .upload(Options::new(UploadFile::from(Vec::new()), "empty"))
+            .await;
+        let unnamed = imagekit
+            .upload(Options::new(asset("assets/ferris.jpeg"), ""))
+            .await;
+
+        assert_eq!(
+            empty.unwrap_err().to_string(),
+            "Missing file parameter for upload"
+        );
+        assert_eq!(
+            unnamed.unwrap_err().to_string(),
+            "Missing fileName parameter for upload"
+        );
+        assert!(imagekit.files().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fails_for_missing_and_invalid_file_ids() {
+        let imagekit = fake();
+        let uploaded = imagekit
+            .upload(Options::new(asset("assets/ferris.jpeg"), "ferris"))
+            .await
+            .unwrap();
+
+        imagekit.delete(&uploaded.file_id).await.unwrap();
+
+        assert!(!imagekit.contains(&uploaded.file_id));
+        assert_eq!(
+            imagekit
+                .get_file_details(&uploaded.file_id)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "The requested file does not exist."
+        );
+        assert_eq!(
+            imagekit
+                .delete(&uploaded.file_id)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "The requested file does not exist."
+        );
+        assert_eq!(
+            imagekit.delete("ferris").await.unwrap_err().to_string(),
+            "Your request contains invalid fileId parameter."
+        );
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod mock_server_tests {
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+
+    use super::accounts::usage::AccountUsage;
+    use super::accounts::Usage;
+    use super::delete::Delete;
+    use super::file_details::Details;
+    use super::http::{HttpRequest, HttpTransport, Method, ReqwestTransport};
+    use super::mock_server::{MockServer, URL_ENDPOINT};
+    use super::phash::{group_duplicates, Perceptual, DEFAULT_THRESHOLD};
+    use super::test_utils::ferris;
+    use super::upload::types::{FileType, ResponseField};
+    use super::upload::{Options, Upload};
+    use super::{ImageKit, PHash};
+
+    #[tokio::test]
+    async fn uploads_over_http() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+        let uploaded = imagekit
+            .upload(Options::new(ferris(), "ferris.jpeg"))
+            .await
+            .unwrap();
+
+        assert_eq!(uploaded.file_id.len(), 24);
+        assert!(uploaded.name.starts_with("ferris_"));
+        assert_eq!(uploaded.url, format!("{URL_ENDPOINT}/{}", uploaded.name));
+        assert_eq!(uploaded.version_info.name, "Version 1");
+        assert_eq!(uploaded.width, Some(640));
+        assert!(uploaded.thumbnail_url.is_some());
+        assert_eq!(server.files().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn combines_usage_of_long_ranges() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+        let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2023, 7, 20).unwrap();
+        // 200 days are requested as 89, 89 and 22 day ranges, the server
+        // rejects ranges of 90 days or more
+        let usage = imagekit.get_usage(start, end).await.unwrap();
+
+        assert_eq!(
+            usage,
+            AccountUsage {
+                bandwidth_bytes: 3 * 21991583,
+                media_library_storage_bytes: 1067552,
+                video_processing_units_count: 3 * 12,
+                extension_units_count: 3 * 3,
+                original_cache_storage_bytes: 204800,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn retrieves_details_with_the_thumbnail_alias() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+        let uploaded = imagekit
+            .upload(Options::new(ferris(), "ferris.jpeg"))
+            .await
+            .unwrap();
+        let details = imagekit.get_file_details(&uploaded.file_id).await.unwrap();
+
+        assert_eq!(details.file_id, uploaded.file_id);
+        assert_eq!(details.file_type, FileType::Image);
+        assert_eq!(details.size, uploaded.size);
+        assert_eq!(details.thumbnail_url, uploaded.thumbnail_url);
+    }
+
+    #[tokio::test]
+    async fn returns_requested_metadata_with_the_phash() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+        let upload = |response_fields| {
+            let opts = Options::new(ferris(), "ferris.jpeg").response_fields(response_fields);
+
+            imagekit.upload(opts)
+        };
+        let first = upload(vec![ResponseField::Metadata]).await.unwrap();
+        let second = upload(vec![ResponseField::Tags, ResponseField::Metadata])
+            .await
+            .unwrap();
+        let without = upload(Vec::new()).await.unwrap();
+        let metadata = first.metadata.as_ref().unwrap();
+
+        assert_eq!(metadata.width, Some(640));
+        assert_eq!(metadata.size, Some(first.size));
+        assert_eq!(
+            first.phash(),
+            Some("f06830ca9f1e3e90".parse::<PHash>().unwrap())
+        );
+        assert!(without.metadata.is_none());
+
+        let files = [first, second, without];
+        let groups = group_duplicates(&files, DEFAULT_THRESHOLD);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0][0].file_id, files[0].file_id);
+        assert_eq!(groups[0][1].file_id, files[1].file_id);
+    }
+
+    #[tokio::test]
+    async fn returns_error_bodies_of_missing_files() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+        let uploaded = imagekit
+            .upload(Options::new(ferris(), "ferris"))
+            .await
+            .unwrap();
+
+        imagekit.delete(&uploaded.file_id).await.unwrap();
+
+        let details = imagekit.get_file_details(&uploaded.file_id).await;
+        let deleted = imagekit.delete(&uploaded.file_id).await;
+        let invalid = imagekit.delete("ferris").await;
+
+        assert_eq!(
+            details.unwrap_err().to_string(),
+            "The requested file does not exist."
+        );
+        assert_eq!(
+            deleted.unwrap_err().to_string(),
+            "The requested file does not exist."
+        );
+        assert_eq!(
+            invalid.unwrap_err().to_string(),
+            "Your request contains invalid fileId parameter."
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_the_private_key() {
+        let server = MockServer::start().await.unwrap();
+        let transport = ReqwestTransport::new().unwrap();
+        let request = HttpRequest::new(
+            Method::Get,
+            format!("{}/v1/files/64c7a1f20000000000000001/details", server.url()),
+        );
+        let response = transport.send(request).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+
+        assert_eq!(response.status, 401);
+        assert_eq!(
+            body["message"],
+            "Your request does not contain private API key."
+        );
+    }
+
+    #[tokio::test]
+    async fn accepts_client_side_uploads() {
+        let server = MockServer::start().await.unwrap();
+        let backend = server.client().unwrap();
+        let params = backend.generate_authentication_parameters().unwrap();
+        let imagekit = ImageKit::public("public_key", URL_ENDPOINT)
+            .unwrap()
+            .upload_endpoint(server.upload_endpoint());
+        let opts = Options::new(ferris(), "ferris").authentication_parameters(params);
+
+        assert!(imagekit.upload(opts).await.is_ok());
+        assert_eq!(server.files().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn responds_with_rate_limit_headers() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+
+        server.rate_limit(1, Duration::from_secs(60));
+
+        let uploaded = imagekit
+            .upload(Options::new(ferris(), "ferris"))
+            .await
+            .unwrap();
+        let limited = imagekit.get_file_details(&uploaded.file_id).await;
+        let request = HttpRequest::new(
+            Method::Delete,
+            format!("{}/v1/files/{}", server.url(), uploaded.file_id),
+        );
+        let response = ReqwestTransport::new()
+            .unwrap()
+            .send(request)
+            .await
+            .unwrap();
+        let reset: u64 = response
+            .header("X-RateLimit-Reset")
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        assert_eq!(limited.unwrap_err().to_string(), "Too many requests");
+        assert_eq!(response.status, 429);
+        assert_eq!(response.header("X-RateLimit-Limit"), Some("1"));
+        assert_eq!(response.header("X-RateLimit-Interval"), Some("60000"));
+        assert!(reset <= 60000);
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod api_tests {
+    use std::sync::Arc;
+
+    use super::api::{ImageKitApi, MockImageKitApi};
+    use super::file_details::Details;
+    use super::mock_server::MockServer;
+    use super::test_utils::ferris;
+    use super::testing::FakeImageKit;
+    use super::upload::Options;
+    use super::{Delete, Upload};
+
+    /// Deletes the file after retrieving its details, as an application
+    /// service depending on the API would
+    async fn archive(imagekit: &dyn ImageKitApi, file_id: &str) -> anyhow::Result<String> {
+        let details = imagekit.file_details(file_id).await?;
+
+        imagekit.delete_file(file_id).await?;
+        Ok(details.name)
+    }
+
+    #[tokio::test]
+    async fn stores_clients_as_trait_objects() {
+        let server = MockServer::start().await.unwrap();
+        let clients: Vec<Arc<dyn ImageKitApi>> = vec![
+            Arc::new(server.client().unwrap()),
+            Arc::new(FakeImageKit::new("https://ik.imagekit.io/demo")),
+        ];
+
+        for imagekit in clients {
+            let uploaded = imagekit
+                .upload_file(Options::new(ferris(), "ferris.jpeg"))
+                .await
+                .unwrap();
+            let name = archive(imagekit.as_ref(), &uploaded.file_id).await.unwrap();
+
+            assert_eq!(name, uploaded.name);
+            assert!(imagekit.file_details(&uploaded.file_id).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn calls_clients_with_every_trait_in_scope() {
+        let imagekit = FakeImageKit::new("https://ik.imagekit.io/demo");
+        let uploaded = imagekit
+            .upload(Options::new(ferris(), "ferris.jpeg"))
+            .await
+            .unwrap();
+        let details = imagekit.file_details(&uploaded.file_id).await.unwrap();
+
+        assert_eq!(details.file_id, uploaded.file_id);
+        assert!(imagekit.get_file_details(&uploaded.file_id).await.is_ok());
+        assert!(imagekit.delete_file(&uploaded.file_id).await.is_ok());
+        assert!(imagekit.delete(&uploaded.file_id).await.is_err());
+        assert!(imagekit
+            .upload_file(Options::new(ferris(), "ferris.jpeg"))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn mocks_the_api() {
+        let mut mock = MockImageKitApi::new();
+
+        mock.expect_file_details()
+            .withf(|file_id| file_id == "64c7a1f20000000000000001")
+            .times(1)
+            .returning(|_| anyhow::bail!("The requested file does not exist."));
+        mock.expect_delete_file().never();
+
+        let imagekit: Arc<dyn ImageKitApi> = Arc::new(mock);
+        let result = archive(imagekit.as_ref(), "64c7a1f20000000000000001").await;
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "The requested file does not exist."
+        );
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport", feature = "tracing"))]
+mod tracing_tests {
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+    use tracing_core::span::Current;
+
+    use super::delete::Delete;
+    use super::file_details::Details;
+    use super::mock_server::{MockServer, URL_ENDPOINT};
+    use super::upload::{Options, Upload, UploadFile};
+    use super::{ImageKit, Origins, UrlEndpoints};
+
+    type Fields = Vec<(String, String)>;
+
+    /// Records the fields of the spans and events of the crate
+    #[derive(Clone, Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<(&'static Metadata<'static>, Fields)>>>,
+        events: Arc<Mutex<Vec<Fields>>>,
+        stack: Arc<Mutex<Vec<Id>>>,
+    }
+
+    impl Recorder {
+        fn spans(&self, name: &str) -> Vec<Fields> {
+            self.spans
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(metadata, _)| metadata.name() == name)
+                .map(|(_, fields)| fields.clone())
+                .collect()
+        }
+
+        fn values(&self) -> Vec<String> {
+            let spans = self.spans.lock().unwrap();
+            let events = self.events.lock().unwrap();
+
+            spans
+                .iter()
+                .map(|(_, fields)| fields)
+                .chain(events.iter())
+                .flatten()
+                .map(|(_, value)| value.clone())
+                .collect()
+        }
+    }
+
+    struct Visitor<'a>(&'a mut Fields);
+
+    impl Visit for Visitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.push((field.name().to_string(), value.to_string()));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{value:?}")));
+        }
+    }
+
+    fn field<'a>(fields: &'a Fields, name: &str) -> Option<&'a str> {
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target().starts_with("imagekit")
+        }
+
+        fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            let mut fields = Vec::new();
+
+            attributes.record(&mut Visitor(&mut fields));
+            spans.push((attributes.metadata(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+
+            values.record(&mut Visitor(fields));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Vec::new();
+
+            event.record(&mut Visitor(&mut fields));
+            self.events.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, span: &Id) {
+            self.stack.lock().unwrap().push(span.clone());
+        }
+
+        fn exit(&self, _: &Id) {
+            self.stack.lock().unwrap().pop();
+        }
+
+        fn current_span(&self) -> Current {
+            let stack = self.stack.lock().unwrap();
+            let spans = self.spans.lock().unwrap();
+
+            match stack.last() {
+                Some(id) => Current::new(id.clone(), spans[id.into_u64() as usize - 1].0),
+                None => Current::none(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn records_api_calls() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+        let data = std::fs::read("assets/ferris.jpeg").unwrap();
+        let bytes = data.len().to_string();
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let uploaded = imagekit
+            .upload(Options::new(UploadFile::from(data), "ferris"))
+            .await
+            .unwrap();
+
+        imagekit.get_file_details(&uploaded.file_id).await.unwrap();
+        imagekit.delete(&uploaded.file_id).await.unwrap();
+
+        let upload = &recorder.spans("imagekit.upload")[0];
+        let details = &recorder.spans("imagekit.get_file_details")[0];
+        let delete = &recorder.spans("imagekit.delete")[0];
+        let requests = recorder.spans("imagekit.request");
+        let statuses: Vec<Option<&str>> = requests
+            .iter()
+            .map(|fields| field(fields, "status"))
+            .collect();
+
+        assert_eq!(field(upload, "file_name"), Some("ferris"));
+        assert_eq!(field(upload, "bytes"), Some(bytes.as_str()));
+        assert_eq!(field(details, "file_id"), Some(uploaded.file_id.as_str()));
+        assert_eq!(field(delete, "file_id"), Some(uploaded.file_id.as_str()));
+        assert_eq!(statuses, vec![Some("200"), Some("200"), Some("204")]);
+        assert_eq!(
+            field(&requests[2], "endpoint"),
+            Some(format!("{}/v1/files/{}", server.url(), uploaded.file_id).as_str())
+        );
+        assert!(requests
+            .iter()
+            .all(|fields| field(fields, "latency_ms").is_some()));
+    }
+
+    #[tokio::test]
+    async fn records_errors() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        assert!(imagekit
+            .get_file_details("64c7a1f20000000000000001")
+            .await
+            .is_err());
+
+        let requests = recorder.spans("imagekit.request");
+
+        assert_eq!(field(&requests[0], "status"), Some("404"));
+        assert!(recorder
+            .values()
+            .iter()
+            .any(|value| value.contains("The requested file does not exist.")));
+    }
+
+    #[tokio::test]
+    async fn records_account_calls() {
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server.client().unwrap();
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        assert!(imagekit.delete_origin("origin_id").await.is_err());
+        assert!(imagekit.get_url_endpoint("endpoint_id").await.is_err());
+
+        let origin = &recorder.spans("imagekit.delete_origin")[0];
+        let endpoint = &recorder.spans("imagekit.get_url_endpoint")[0];
+
+        assert_eq!(field(origin, "id"), Some("origin_id"));
+        assert_eq!(field(endpoint, "id"), Some("endpoint_id"));
+        assert_eq!(recorder.spans("imagekit.request").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn never_records_credentials() {
+        let server = MockServer::start().await.unwrap();
+        let backend = server.client().unwrap();
+        let params = backend.generate_authentication_parameters().unwrap();
+        let token = params.token.clone();
+        let signature = params.signature.clone();
+        let client = ImageKit::public("public_key", URL_ENDPOINT)
+            .unwrap()
+            .upload_endpoint(server.upload_endpoint());
+        let file = UploadFile::from(std::fs::read("assets/ferris.jpeg").unwrap());
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let uploaded = client
+            .upload(Options::new(file, "ferris").authentication_parameters(params))
+            .await
+            .unwrap();
+
+        backend.delete(&uploaded.file_id).await.unwrap();
+
+        let values = recorder.values();
+
+        assert!(!values.is_empty());
+        assert!(values.iter().all(|value| !value.contains("private_key")
+            && !value.contains("Basic")
+            && !value.contains(&signature)
+            && !value.contains(&token)));
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod metrics_tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::delete::Delete;
+    use super::file_details::Details;
+    use super::metrics::{ErrorKind, MetricsObserver, Operation, RequestMetrics};
+    use super::mock_server::MockServer;
+    use super::upload::{Options, Upload, UploadFile};
+    use super::ImageKit;
+
+    #[derive(Clone, Default)]
+    struct RecordingObserver {
+        requests: Arc<Mutex<Vec<RequestMetrics>>>,
+    }
+
+    impl MetricsObserver for RecordingObserver {
+        fn on_request(&self, metrics: &RequestMetrics) {
+            self.requests.lock().unwrap().push(metrics.clone());
+        }
+    }
+
+    #[test]
+    fn classifies_statuses() {
+        assert_eq!(ErrorKind::from_status(200), None);
+        assert_eq!(ErrorKind::from_status(204), None);
+        assert_eq!(ErrorKind::from_status(400), Some(ErrorKind::Client));
+        assert_eq!(ErrorKind::from_status(401), Some(ErrorKind::Unauthorized));
+        assert_eq!(ErrorKind::from_status(403), Some(ErrorKind::Unauthorized));
+        assert_eq!(ErrorKind::from_status(404), Some(ErrorKind::NotFound));
+        assert_eq!(ErrorKind::from_status(429), Some(ErrorKind::RateLimited));
+        assert_eq!(ErrorKind::from_status(503), Some(ErrorKind::Server));
+    }
+
+    #[tokio::test]
+    async fn observes_every_request() {
+        let server = MockServer::start().await.unwrap();
+        let observer = RecordingObserver::default();
+        let imagekit = server.client().unwrap().metrics_observer(observer.clone());
+        let data = std::fs::read("assets/ferris.jpeg").unwrap();
+        let size = data.len() as u64;
+        let uploaded = imagekit
+            .upload(Options::new(UploadFile::from(data), "ferris"))
+            .await
+            .unwrap();
+
+        imagekit.get_file_details(&uploaded.file_id).await.unwrap();
+        imagekit.delete(&uploaded.file_id).await.unwrap();
+        assert!(imagekit.delete(&uploaded.file_id).await.is_err());
+
+        let requests = observer.requests.lock().unwrap();
+        let outcomes: Vec<(Operation, Option<u16>, Option<ErrorKind>)> = requests
+            .iter()
+            .map(|metrics| (metrics.operation, metrics.status, metrics.error))
+            .collect();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                (Operation::Upload, Some(200), None),
+                (Operation::GetFileDetails, Some(200), None),
+                (Operation::Delete, Some(204), None),
+                (Operation::Delete, Some(404), Some(ErrorKind::NotFound)),
+            ]
+        );
+        assert!(requests[0].bytes_sent > size);
+        assert_eq!(requests[1].bytes_sent, 0);
+    }
+
+    #[tokio::test]
+    async fn observes_rate_limited_and_client_side_requests() {
+        let server = MockServer::start().await.unwrap();
+        let observer = RecordingObserver::default();
+        let backend = server.client().unwrap();
+        let params = backend.generate_authentication_parameters().unwrap();
+        let imagekit = ImageKit::public("public_key", "https://ik.imagekit.io/demo")
+            .unwrap()
+            .upload_endpoint(server.upload_endpoint())
+            .metrics_observer(observer.clone());
+        let file = UploadFile::from(std::fs::read("assets/ferris.jpeg").unwrap());
+
+        server.rate_limit(0, Duration::from_secs(60));
+
+        assert!(imagekit
+            .upload(Options::new(file, "ferris").authentication_parameters(params))
+            .await
+            .is_err());
+
+        let requests = observer.requests.lock().unwrap();
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].operation, Operation::Upload);
+        assert_eq!(requests[0].status, Some(429));
+        assert_eq!(requests[0].error, Some(ErrorKind::RateLimited));
+    }
+
+    #[tokio::test]
+    async fn observes_transport_errors() {
+        let observer = RecordingObserver::default();
+        let imagekit = ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo")
+            .unwrap()
+            .api_endpoint("http://127.0.0.1:1")
+            .metrics_observer(observer.clone());
+
+        assert!(imagekit
+            .get_file_details("64c7a1f20000000000000001")
+            .await
+            .is_err());
+
+        let requests = observer.requests.lock().unwrap();
+
+        assert_eq!(requests[0].operation, Operation::GetFileDetails);
+        assert_eq!(requests[0].status, None);
+        assert_eq!(requests[0].error, Some(ErrorKind::Transport));
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport", feature = "metrics"))]
+mod metrics_crate_tests {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    use super::delete::Delete;
+    use super::metrics::{
+        MetricsCrateObserver, REQUESTS_TOTAL, REQUEST_DURATION_SECONDS, REQUEST_ERRORS_TOTAL,
+        UPLOAD_BYTES_TOTAL,
+    };
+    use super::mock_server::MockServer;
+    use super::upload::{Options, Upload, UploadFile};
+
+    #[tokio::test]
+    async fn records_metrics_with_the_metrics_crate() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        recorder.install().unwrap();
+
+        let server = MockServer::start().await.unwrap();
+        let imagekit = server
+            .client()
+            .unwrap()
+            .metrics_observer(MetricsCrateObserver);
+        let uploaded = imagekit
+            .upload(Options::new(
+                UploadFile::from(std::fs::read("assets/ferris.jpeg").unwrap()),
+                "ferris",
+            ))
+            .await
+            .unwrap();
+
+        imagekit.delete(&uploaded.file_id).await.unwrap();
+        assert!(imagekit.delete(&uploaded.file_id).await.is_err());
+
+        let metrics: Vec<(String, Vec<String>, DebugValue)> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let (_, key) = key.into_parts();
+                let labels = key
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect();
+
+                (key.name().to_string(), labels, value)
+            })
+            .collect();
+        let find = |name: &str, labels: &[&str]| {
+            metrics
+                .iter()
+                .find(|(metric, metric_labels, _)| metric == name && metric_labels == labels)
+                .map(|(_, _, value)| value)
+        };
+
+        assert_eq!(
+            find(REQUESTS_TOTAL, &["operation=upload", "status=200"]),
+            Some(&DebugValue::Counter(1))
+        );
+        assert_eq!(
+            find(REQUESTS_TOTAL, &["operation=delete", "status=204"]),
+            Some(&DebugValue::Counter(1))
+        );
+        assert_eq!(
+            find(
+                REQUEST_ERRORS_TOTAL,
+                &["operation=delete", "kind=not_found"]
+            ),
+            Some(&DebugValue::Counter(1))
+        );
+        assert!(matches!(
+            find(REQUEST_DURATION_SECONDS, &["operation=delete"]),
+            Some(DebugValue::Histogram(values)) if values.len() == 2
+        ));
+        assert!(matches!(
+            find(UPLOAD_BYTES_TOTAL, &[]),
+            Some(DebugValue::Counter(bytes)) if *bytes > uploaded.size
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "reqwest-transport"))]
+mod secret_tests {
+    use super::client::{authenticate, authorization};
+    use super::http::{HttpRequest, Method};
+    use super::types::Secret;
+    use super::ImageKit;
+
+    #[test]
+    fn redacts_secrets_when_formatted() {
+        let secret = Secret::new("private_key");
+
+        assert_eq!(format!("{secret:?}"), "Secret([REDACTED])");
+        assert_eq!(format!("{secret}"), "[REDACTED]");
+        assert_eq!(secret.expose(), "private_key");
+    }
+
+    #[test]
+    fn compares_secrets() {
+        assert_eq!(Secret::new("private_key"), Secret::new("private_key"));
+        assert_ne!(Secret::new("private_key"), Secret::new("private_kez"));
+        assert_ne!(Secret::new("private_key"), Secret::new("private_key_2"));
+        assert_ne!(Secret::new(""), Secret::new("private_key"));
+    }
+
+    #[test]
+    fn formats_clients_without_the_private_key() {
+        let imagekit =
+            ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap();
+
+        assert_eq!(
+            format!("{imagekit:?}"),
+            r#"ImageKit { public_key: "public_key", url_endpoint: "https://ik.imagekit.io/demo", .. }"#
+        );
+    }
+
+    #[test]
+    fn builds_the_authorization_header_once() {
+        let imagekit =
+            ImageKit::new("public_key", "private_key", "https://ik.imagekit.io/demo").unwrap();
+
+        assert_eq!(
+            authorization("private_key").expose(),
+            "Basic cHJpdmF0ZV9rZXk6"
+        );
+        assert_eq!(
+            imagekit
+                .authorization
+                .as_ref()
+                .map(|header| header.expose()),
+            Some("Basic cHJpdmF0ZV9rZXk6")
+        );
+    }
+
+    #[test]
+    fn redacts_the_authorization_header_of_requests() {
+        let request = authenticate(
+            HttpRequest::new(Method::Delete, "https://api.imagekit.io/v1/files/1")
+                .header("Content-Type", "application/json"),
+            &authorization("private_key"),
+        );
+        let formatted = format!("{request:?}");
+
+        assert!(request
+            .headers
+            .iter()
+            .any(|(key, _)| key == "Authorization"));
+        assert!(formatted.contains(r#"("Authorization", "[REDACTED]")"#));
+        assert!(formatted.contains(r#"("Content-Type", "application/json")"#));
+        assert!(!formatted.contains("Basic"));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn formats_blocking_clients_without_the_private_key() {
+        let imagekit = super::blocking::ImageKit::new(
+            "public_key",
+            "private_key",
+            "https://ik.imagekit.io/demo",
+        )
+        .unwrap();
+        let formatted = format!("{imagekit:?}");
+
+        assert!(formatted.contains("public_key"));
+        assert!(!formatted.contains("private_key"));
+    }
+}
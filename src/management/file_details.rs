@@ -1,10 +1,12 @@
 use std::fmt::Display;
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::StatusCode;
 
-use crate::{client::FILES_ENDPOINT, upload::types::Response, ErrorResponse, ImageKit};
+use crate::client::parse_response;
+use crate::http::{segment, HttpRequest, Method};
+use crate::metrics::Operation;
+use crate::{upload::types::Response, ImageKit};
 
 #[async_trait]
 pub trait Details {
@@ -14,21 +16,31 @@ pub trait Details {
 
 #[async_trait]
 impl Details for ImageKit {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "imagekit.get_file_details",
+            skip_all,
+            fields(file_id = %id),
+            err
+        )
+    )]
     async fn get_file_details<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<Response> {
         let response = self
-            .client
-            .get(format!("{FILES_ENDPOINT}/{id}/details"))
-            .send()
+            .send(
+                Operation::GetFileDetails,
+                request(&self.files_endpoint(), id.as_ref()),
+            )
             .await?;
 
-        if matches!(response.status(), StatusCode::OK) {
-            let result = response.json::<Response>().await.unwrap();
-
-            return Ok(result);
-        }
-
-        let result = response.json::<ErrorResponse>().await.unwrap();
-
-        bail!(result.message);
+        parse_response(&response, 200)
     }
 }
+
+/// Builds the request retrieving the details of the file with `id`
+pub(crate) fn request(files_endpoint: &str, id: &str) -> HttpRequest {
+    HttpRequest::new(
+        Method::Get,
+        format!("{files_endpoint}/{}/details", segment(id)),
+    )
+}
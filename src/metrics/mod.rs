@@ -0,0 +1,160 @@
+//! Metrics of the requests sent to the ImageKit API.
+//!
+//! Every request sent by `ImageKit` and `imagekit::blocking::ImageKit`,
+//! including the listing of origins and URL endpoints, is reported to the
+//! `MetricsObserver` set with `metrics_observer` and, with the `tracing`
+//! feature, recorded in an `imagekit.request` span.
+//!
+//! Retries are out of scope: the clients send each request once and leave
+//! retrying, e.g. on `429 Too Many Requests`, to the caller, so no retry
+//! count is recorded. Listing files is not implemented by the crate, so it
+//! has no `Operation`.
+#[cfg(feature = "metrics")]
+mod recorder;
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::http::HttpResponse;
+
+#[cfg(feature = "metrics")]
+pub use self::recorder::{
+    MetricsCrateObserver, REQUESTS_TOTAL, REQUEST_DURATION_SECONDS, REQUEST_ERRORS_TOTAL,
+    UPLOAD_BYTES_TOTAL,
+};
+
+/// Operation of the API a request is sent for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Upload,
+    Delete,
+    GetFileDetails,
+    GetUsage,
+    ListOrigins,
+    GetOrigin,
+    CreateOrigin,
+    UpdateOrigin,
+    DeleteOrigin,
+    ListUrlEndpoints,
+    GetUrlEndpoint,
+    CreateUrlEndpoint,
+    UpdateUrlEndpoint,
+    DeleteUrlEndpoint,
+}
+
+impl Operation {
+    /// Name of the operation, used as label of the metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Upload => "upload",
+            Operation::Delete => "delete",
+            Operation::GetFileDetails => "get_file_details",
+            Operation::GetUsage => "get_usage",
+            Operation::ListOrigins => "list_origins",
+            Operation::GetOrigin => "get_origin",
+            Operation::CreateOrigin => "create_origin",
+            Operation::UpdateOrigin => "update_origin",
+            Operation::DeleteOrigin => "delete_origin",
+            Operation::ListUrlEndpoints => "list_url_endpoints",
+            Operation::GetUrlEndpoint => "get_url_endpoint",
+            Operation::CreateUrlEndpoint => "create_url_endpoint",
+            Operation::UpdateUrlEndpoint => "update_url_endpoint",
+            Operation::DeleteUrlEndpoint => "delete_url_endpoint",
+        }
+    }
+}
+
+/// Kind of error of a failed request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The request could not be sent or its response could not be read
+    Transport,
+    /// `401 Unauthorized` or `403 Forbidden`
+    Unauthorized,
+    /// `404 Not Found`
+    NotFound,
+    /// `429 Too Many Requests`
+    RateLimited,
+    /// Other `4xx` statuses
+    Client,
+    /// `5xx` statuses
+    Server,
+}
+
+impl ErrorKind {
+    /// Kind of error of a response status, `None` for successful statuses
+    pub fn from_status(status: u16) -> Option<Self> {
+        match status {
+            401 | 403 => Some(ErrorKind::Unauthorized),
+            404 => Some(ErrorKind::NotFound),
+            429 => Some(ErrorKind::RateLimited),
+            400..=499 => Some(ErrorKind::Client),
+            500..=599 => Some(ErrorKind::Server),
+            _ => None,
+        }
+    }
+
+    /// Name of the kind of error, used as label of the metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Transport => "transport",
+            ErrorKind::Unauthorized => "unauthorized",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::Client => "client",
+            ErrorKind::Server => "server",
+        }
+    }
+}
+
+/// Outcome of a request sent by `ImageKit`, provided to the
+/// `MetricsObserver` of the client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestMetrics {
+    pub operation: Operation,
+    /// Status of the response, `None` when no response was received
+    pub status: Option<u16>,
+    /// Kind of error of failed requests
+    pub error: Option<ErrorKind>,
+    /// Time from sending the request to receiving the whole response
+    pub latency: Duration,
+    /// Size of the request body in Bytes, including the `multipart/form-data`
+    /// encoding of uploads
+    pub bytes_sent: u64,
+}
+
+impl RequestMetrics {
+    pub(crate) fn new(
+        operation: Operation,
+        response: &Result<HttpResponse>,
+        latency: Duration,
+        bytes_sent: u64,
+    ) -> Self {
+        let status = response.as_ref().ok().map(|response| response.status);
+        let error = match status {
+            Some(status) => ErrorKind::from_status(status),
+            None => Some(ErrorKind::Transport),
+        };
+
+        Self {
+            operation,
+            status,
+            error,
+            latency,
+            bytes_sent,
+        }
+    }
+}
+
+/// Receives the outcome of every request sent by `ImageKit`, to export
+/// request counts, errors, latencies and uploaded bytes:
+///
+/// ```ignore
+/// let imagekit = ImageKit::from_env()?.metrics_observer(MyObserver);
+/// ```
+pub trait MetricsObserver: Send + Sync {
+    /// Called once the response of the request is received or the request
+    /// fails
+    fn on_request(&self, metrics: &RequestMetrics);
+}
@@ -0,0 +1,46 @@
+use super::{MetricsObserver, Operation, RequestMetrics};
+
+/// Total of requests, labeled with `operation` and `status`
+pub const REQUESTS_TOTAL: &str = "imagekit_requests_total";
+/// Total of failed requests, labeled with `operation` and `kind`
+pub const REQUEST_ERRORS_TOTAL: &str = "imagekit_request_errors_total";
+/// Histogram of the latency of requests in seconds, labeled with
+/// `operation`
+pub const REQUEST_DURATION_SECONDS: &str = "imagekit_request_duration_seconds";
+/// Total of Bytes sent by uploads
+pub const UPLOAD_BYTES_TOTAL: &str = "imagekit_upload_bytes_total";
+
+/// `MetricsObserver` recording the requests with the `metrics` crate,
+/// available with the `metrics` feature. Metrics are exported by the
+/// recorder installed by the application, e.g. a Prometheus exporter.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetricsCrateObserver;
+
+impl MetricsObserver for MetricsCrateObserver {
+    fn on_request(&self, metrics: &RequestMetrics) {
+        let operation = metrics.operation.as_str();
+        let status = metrics
+            .status
+            .map_or_else(|| "none".to_string(), |status| status.to_string());
+
+        ::metrics::counter!(REQUESTS_TOTAL, 1, "operation" => operation, "status" => status);
+        ::metrics::histogram!(
+            REQUEST_DURATION_SECONDS,
+            metrics.latency.as_secs_f64(),
+            "operation" => operation
+        );
+
+        if let Some(kind) = metrics.error {
+            ::metrics::counter!(
+                REQUEST_ERRORS_TOTAL,
+                1,
+                "operation" => operation,
+                "kind" => kind.as_str()
+            );
+        }
+
+        if metrics.operation == Operation::Upload && metrics.error.is_none() {
+            ::metrics::counter!(UPLOAD_BYTES_TOTAL, metrics.bytes_sent);
+        }
+    }
+}
@@ -0,0 +1,466 @@
+//! Local HTTP server speaking the ImageKit upload, files and usage APIs,
+//! available with the `mock-server` feature.
+//!
+//! Responses are rendered from JSON fixtures recorded from the ImageKit API,
+//! files are kept in memory by a `FakeImageKit`:
+//!
+//! ```ignore
+//! let server = MockServer::start().await?;
+//! let imagekit = server.client()?;
+//! let file = UploadFile::from(std::fs::read("assets/ferris.jpeg")?);
+//! let uploaded = imagekit.upload(Options::new(file, "ferris")).await?;
+//!
+//! imagekit.delete(&uploaded.file_id).await?;
+//! ```
+use std::convert::Infallible;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use hyper::header::HeaderValue;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde_json::{json, Value};
+use tokio::sync::oneshot;
+
+use crate::delete::Delete;
+use crate::file_details::Details;
+use crate::testing::FakeImageKit;
+use crate::upload::types::{self, FileType};
+use crate::upload::{Options, Upload, UploadFile};
+
+const UPLOAD_FIXTURE: &str = include_str!("fixtures/upload.json");
+const DETAILS_FIXTURE: &str = include_str!("fixtures/details.json");
+const METADATA_FIXTURE: &str = include_str!("fixtures/metadata.json");
+const ERROR_FIXTURE: &str = include_str!("fixtures/error.json");
+const RATE_LIMIT_FIXTURE: &str = include_str!("fixtures/rate_limit.json");
+const USAGE_FIXTURE: &str = include_str!("fixtures/usage.json");
+
+/// Path of the upload API, relative to the URL of the server
+pub const UPLOAD_PATH: &str = "/api/v1/files/upload";
+
+/// URL endpoint of the files uploaded to the server
+pub const URL_ENDPOINT: &str = "https://ik.imagekit.io/demo";
+
+const FILES_PATH: &str = "/v1/files/";
+const USAGE_PATH: &str = "/v1/accounts/usage";
+const MISSING_PRIVATE_KEY: &str = "Your request does not contain private API key.";
+const NOT_FOUND: &str = "The requested resource does not exist.";
+
+/// Requests allowed in each interval, ImageKit reports the limit with the
+/// `X-RateLimit-*` headers of `429 Too Many Requests` responses.
+///
+/// Refer: https://docs.imagekit.io/api-reference/api-introduction/rate-limits
+struct RateLimit {
+    limit: u64,
+    interval: Duration,
+    window_start: Instant,
+    requests: u64,
+}
+
+struct State {
+    files: FakeImageKit,
+    rate_limit: Mutex<Option<RateLimit>>,
+}
+
+/// Local server mirroring the ImageKit upload, files and usage APIs, stopped
+/// when dropped.
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<State>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// Starts a server on a random local port, must be called within a
+    /// `tokio` runtime
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(State {
+            files: FakeImageKit::new(URL_ENDPOINT),
+            rate_limit: Mutex::new(None),
+        });
+        let service_state = Arc::clone(&state);
+        let make_service = make_service_fn(move |_| {
+            let state = Arc::clone(&service_state);
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let state = Arc::clone(&state);
+
+                    async move { Ok::<_, Infallible>(handle(&state, request).await) }
+                }))
+            }
+        });
+        let (shutdown, signal) = oneshot::channel::<()>();
+
+        listener.set_nonblocking(true)?;
+
+        let server = Server::from_tcp(listener)?
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                signal.await.ok();
+            });
+
+        tokio::spawn(server);
+
+        Ok(Self {
+            address,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// Base URL of the server, to be used as the `api_endpoint` of the
+    /// client
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Endpoint of the upload API, to be used as the `upload_endpoint` of
+    /// the client
+    pub fn upload_endpoint(&self) -> String {
+        format!("{}{UPLOAD_PATH}", self.url())
+    }
+
+    /// Creates a client sending its requests to the server
+    #[cfg(feature = "reqwest-transport")]
+    pub fn client(&self) -> Result<crate::ImageKit> {
+        let imagekit = crate::ImageKit::new("public_key", "private_key", URL_ENDPOINT)?
+            .api_endpoint(self.url())
+            .upload_endpoint(self.upload_endpoint());
+
+        Ok(imagekit)
+    }
+
+    /// Creates a blocking client sending its requests to the server, it must
+    /// be used outside of the runtime running the server
+    #[cfg(feature = "blocking")]
+    pub fn blocking_client(&self) -> Result<crate::blocking::ImageKit> {
+        let imagekit = crate::blocking::ImageKit::new("public_key", "private_key", URL_ENDPOINT)?
+            .api_endpoint(self.url())
+            .upload_endpoint(self.upload_endpoint());
+
+        Ok(imagekit)
+    }
+
+    /// Responds with `429 Too Many Requests` once `limit` requests are
+    /// received within `interval`
+    pub fn rate_limit(&self, limit: u64, interval: Duration) {
+        *lock(&self.state.rate_limit) = Some(RateLimit {
+            limit,
+            interval,
+            window_start: Instant::now(),
+            requests: 0,
+        });
+    }
+
+    /// Returns the files stored by the server in upload order
+    pub fn files(&self) -> Vec<types::Response> {
+        self.state.files.files()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+async fn handle(state: &State, request: Request<Body>) -> Response<Body> {
+    if let Some(response) = rate_limited(state) {
+        return response;
+    }
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let authorized = request
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("Basic "));
+
+    if method == Method::POST && path == UPLOAD_PATH {
+        return upload(state, request, authorized).await;
+    }
+
+    if method == Method::GET && path == USAGE_PATH {
+        if !authorized {
+            return error(StatusCode::UNAUTHORIZED, MISSING_PRIVATE_KEY);
+        }
+
+        return usage(request.uri().query().unwrap_or_default());
+    }
+
+    let Some(file_path) = path.strip_prefix(FILES_PATH) else {
+        return error(StatusCode::NOT_FOUND, NOT_FOUND);
+    };
+
+    if !authorized {
+        return error(StatusCode::UNAUTHORIZED, MISSING_PRIVATE_KEY);
+    }
+
+    match (method, file_path.split_once('/')) {
+        (Method::GET, Some((file_id, "details"))) => {
+            match state.files.get_file_details(file_id).await {
+                Ok(file) => json(StatusCode::OK, render(DETAILS_FIXTURE, &file)),
+                Err(err) => file_error(err),
+            }
+        }
+        (Method::DELETE, None) => match state.files.delete(file_path).await {
+            Ok(()) => empty(StatusCode::NO_CONTENT),
+            Err(err) => file_error(err),
+        },
+        _ => error(StatusCode::NOT_FOUND, NOT_FOUND),
+    }
+}
+
+async fn upload(state: &State, request: Request<Body>, authorized: bool) -> Response<Body> {
+    let content_type = request
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let Ok(body) = hyper::body::to_bytes(request.into_body()).await else {
+        return error(StatusCode::BAD_REQUEST, "Invalid request body");
+    };
+    let fields = multipart_fields(&content_type, &body).unwrap_or_default();
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    // Client side uploads are authenticated with the public key and a
+    // signature instead of the private key
+    if !authorized && field("signature").is_none() {
+        return error(StatusCode::UNAUTHORIZED, MISSING_PRIVATE_KEY);
+    }
+
+    let file_name = field("fileName")
+        .map(|name| String::from_utf8_lossy(&name).into_owned())
+        .unwrap_or_default();
+    let file = UploadFile::from(field("file").unwrap_or_default());
+    let response_fields = field("responseFields")
+        .map(|fields| String::from_utf8_lossy(&fields).into_owned())
+        .unwrap_or_default();
+
+    match state.files.upload(Options::new(file, file_name)).await {
+        Ok(file) => {
+            let mut value = render(UPLOAD_FIXTURE, &file);
+
+            if response_fields.split(',').any(|field| field == "metadata") {
+                value["metadata"] = render(METADATA_FIXTURE, &file);
+            }
+
+            json(StatusCode::OK, value)
+        }
+        Err(err) => error(StatusCode::BAD_REQUEST, &err.to_string()),
+    }
+}
+
+/// Answers usage requests with the recorded usage, ranges must be shorter
+/// than 90 days as in the ImageKit API
+fn usage(query: &str) -> Response<Body> {
+    let date = |name: &str| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok())
+    };
+
+    match (date("startDate"), date("endDate")) {
+        (Some(start), Some(end)) if start < end && (end - start).num_days() < 90 => {
+            fixture(StatusCode::OK, USAGE_FIXTURE)
+        }
+        _ => error(
+            StatusCode::BAD_REQUEST,
+            "The date range must be shorter than 90 days",
+        ),
+    }
+}
+
+/// Counts the request, returning a `429 Too Many Requests` response once
+/// the limit of the current interval is reached
+fn rate_limited(state: &State) -> Option<Response<Body>> {
+    let mut rate_limit = lock(&state.rate_limit);
+    let rate_limit = rate_limit.as_mut()?;
+    let elapsed = rate_limit.window_start.elapsed();
+
+    if elapsed >= rate_limit.interval {
+        rate_limit.window_start = Instant::now();
+        rate_limit.requests = 0;
+    }
+
+    if rate_limit.requests < rate_limit.limit {
+        rate_limit.requests += 1;
+        return None;
+    }
+
+    let reset = rate_limit.interval.saturating_sub(elapsed);
+    let mut response = fixture(StatusCode::TOO_MANY_REQUESTS, RATE_LIMIT_FIXTURE);
+    let headers = response.headers_mut();
+
+    headers.insert("X-RateLimit-Limit", rate_limit.limit.into());
+    headers.insert("X-RateLimit-Reset", (reset.as_millis() as u64).into());
+    headers.insert(
+        "X-RateLimit-Interval",
+        (rate_limit.interval.as_millis() as u64).into(),
+    );
+
+    Some(response)
+}
+
+/// Replaces the file fields of a fixture with the ones of `file`
+fn render(fixture: &str, file: &types::Response) -> Value {
+    let mut value: Value = serde_json::from_str(fixture).expect("fixtures are valid JSON");
+    let thumbnail = file.thumbnail_url.clone().map_or(Value::Null, Value::from);
+    let fields = json!({
+        "fileId": file.file_id,
+        "name": file.name,
+        "size": file.size,
+        "versionInfo": {
+            "id": file.version_info.id,
+            "name": file.version_info.name,
+        },
+        "filePath": file.file_path,
+        "url": file.url,
+        "fileType": match file.file_type {
+            FileType::Image => "image",
+            FileType::NonImage => "non-image",
+        },
+        "height": file.height,
+        "width": file.width,
+        "mime": mime_type(&file.name),
+    });
+
+    if let (Some(object), Some(fields)) = (value.as_object_mut(), fields.as_object()) {
+        for (key, field) in fields {
+            if object.contains_key(key) {
+                object.insert(key.clone(), field.clone());
+            }
+        }
+
+        // Uploads return the thumbnail as `thumbnailUrl` and file details as
+        // `thumbnail`
+        for key in ["thumbnailUrl", "thumbnail"] {
+            if object.contains_key(key) {
+                object.insert(key.to_string(), thumbnail.clone());
+            }
+        }
+    }
+
+    value
+}
+
+fn mime_type(file_name: &str) -> &'static str {
+    match file_name.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Reads the fields of a `multipart/form-data` body
+fn multipart_fields(content_type: &str, body: &[u8]) -> Option<Vec<(String, Vec<u8>)>> {
+    let boundary = content_type
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))?
+        .trim_matches('"');
+    let delimiter = format!("--{boundary}");
+    let mut fields = Vec::new();
+
+    for part in split(body, delimiter.as_bytes()).into_iter().skip(1) {
+        if part.starts_with(b"--") {
+            break;
+        }
+
+        let part = part.strip_prefix(b"\r\n")?;
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+        let header_end = find(part, b"\r\n\r\n")?;
+        let headers = String::from_utf8_lossy(&part[..header_end]);
+        let name = headers
+            .split(';')
+            .find_map(|param| param.trim().strip_prefix("name="))?
+            .trim_matches('"')
+            .to_string();
+
+        fields.push((name, part[header_end + 4..].to_vec()));
+    }
+
+    Some(fields)
+}
+
+fn split<'a>(data: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    let mut rest = data;
+
+    while let Some(index) = find(rest, delimiter) {
+        parts.push(&rest[..index]);
+        rest = &rest[index + delimiter.len()..];
+    }
+
+    parts.push(rest);
+    parts
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Maps the errors of the `FakeImageKit` to the status codes of the API
+fn file_error(err: anyhow::Error) -> Response<Body> {
+    let message = err.to_string();
+    let status = if message.contains("does not exist") {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+
+    error(status, &message)
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Body> {
+    let mut value: Value = serde_json::from_str(ERROR_FIXTURE).expect("fixtures are valid JSON");
+
+    value["message"] = Value::from(message);
+    json(status, value)
+}
+
+fn fixture(status: StatusCode, fixture: &'static str) -> Response<Body> {
+    response(status, Body::from(fixture))
+}
+
+fn json(status: StatusCode, value: Value) -> Response<Body> {
+    response(status, Body::from(value.to_string()))
+}
+
+fn empty(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+
+    *response.status_mut() = status;
+    response
+}
+
+fn response(status: StatusCode, body: Body) -> Response<Body> {
+    let mut response = Response::new(body);
+
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert("Content-Type", HeaderValue::from_static("application/json"));
+    response
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
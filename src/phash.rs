@@ -0,0 +1,149 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+
+use crate::upload::types::Response;
+
+/// Maximum distance between two perceptual hashes, given they are 64 bits
+/// long.
+pub const MAX_DISTANCE: u32 = 64;
+
+/// Distance under which two images are usually considered near-duplicates.
+pub const DEFAULT_THRESHOLD: u32 = 10;
+
+/// Perceptual Hash (pHash) of an image as returned by ImageKit in the file
+/// metadata.
+///
+/// Refer: https://docs.imagekit.io/api-reference/metadata-api#perceptual-hash-phash
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PHash(u64);
+
+impl PHash {
+    /// Creates a `PHash` from its numeric representation
+    pub fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Retrieves the numeric representation of this hash
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Hamming distance between two hashes, `0` for identical images and up
+    /// to `MAX_DISTANCE` for completely different ones.
+    pub fn distance(&self, other: &PHash) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    /// Whether the distance to `other` is less than or equal to `threshold`.
+    pub fn is_similar(&self, other: &PHash, threshold: u32) -> bool {
+        self.distance(other) <= threshold
+    }
+
+    /// Similarity between both hashes as a value between `0.0` (completely
+    /// different) and `1.0` (identical).
+    pub fn similarity(&self, other: &PHash) -> f32 {
+        1.0 - self.distance(other) as f32 / MAX_DISTANCE as f32
+    }
+}
+
+impl FromStr for PHash {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.len() != 16 || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("Invalid pHash \"{s}\", expected 16 hexadecimal characters");
+        }
+
+        Ok(Self(u64::from_str_radix(s, 16)?))
+    }
+}
+
+impl Display for PHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Types holding a perceptual hash, such as file objects returned by the
+/// ImageKit API.
+pub trait Perceptual {
+    /// Retrieves the perceptual hash if available
+    fn phash(&self) -> Option<PHash>;
+}
+
+impl Perceptual for PHash {
+    fn phash(&self) -> Option<PHash> {
+        Some(*self)
+    }
+}
+
+/// The pHash is only returned for uploads requesting
+/// `ResponseField::Metadata`
+impl Perceptual for Response {
+    fn phash(&self) -> Option<PHash> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.p_hash.as_ref())
+            .and_then(|p_hash| p_hash.parse().ok())
+    }
+}
+
+/// Groups files into clusters of near-duplicates.
+///
+/// Two files belong to the same cluster when the distance between their
+/// hashes is less than or equal to `threshold`, or when they are both
+/// near-duplicates of a third file in the cluster. Files without a pHash and
+/// files without duplicates are not included in the output.
+pub fn group_duplicates<T: Perceptual>(files: &[T], threshold: u32) -> Vec<Vec<&T>> {
+    let hashes: Vec<Option<PHash>> = files.iter().map(Perceptual::phash).collect();
+    let mut parents: Vec<usize> = (0..files.len()).collect();
+
+    for (i, a) in hashes.iter().enumerate() {
+        let Some(a) = a else {
+            continue;
+        };
+
+        for (j, b) in hashes.iter().enumerate().skip(i + 1) {
+            if matches!(b, Some(b) if a.is_similar(b, threshold)) {
+                let root_a = find_root(&mut parents, i);
+                let root_b = find_root(&mut parents, j);
+                parents[root_b] = root_a;
+            }
+        }
+    }
+
+    let mut clusters: Vec<(usize, Vec<&T>)> = Vec::new();
+
+    for (i, file) in files.iter().enumerate() {
+        if hashes[i].is_none() {
+            continue;
+        }
+
+        let root = find_root(&mut parents, i);
+
+        match clusters
+            .iter_mut()
+            .find(|(cluster_root, _)| *cluster_root == root)
+        {
+            Some((_, cluster)) => cluster.push(file),
+            None => clusters.push((root, vec![file])),
+        }
+    }
+
+    clusters
+        .into_iter()
+        .map(|(_, cluster)| cluster)
+        .filter(|cluster| cluster.len() > 1)
+        .collect()
+}
+
+fn find_root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+
+    node
+}
@@ -0,0 +1,253 @@
+//! In-memory fake of the ImageKit API for unit tests, available with the
+//! `testing` feature.
+//!
+//! `FakeImageKit` implements the same traits as `ImageKit`, so code generic
+//! over `Upload`, `Delete` or `Details` can be tested without credentials or
+//! network access:
+//!
+//! ```ignore
+//! let imagekit = FakeImageKit::new("https://ik.imagekit.io/demo");
+//! let file = UploadFile::from(std::fs::read("assets/ferris.jpeg")?);
+//! let uploaded = imagekit.upload(Options::new(file, "ferris.jpeg")).await?;
+//!
+//! assert_eq!(uploaded.width, Some(640));
+//! assert!(imagekit.delete(&uploaded.file_id).await.is_ok());
+//! assert!(imagekit.get_file_details(&uploaded.file_id).await.is_err());
+//! ```
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+
+use crate::delete::Delete;
+use crate::file_details::Details;
+use crate::upload::types::{FileType, Response, VersionInfo};
+use crate::upload::{Options, Upload};
+use crate::url::{generate_url, Options as UrlOptions, Url};
+
+/// Prefix of the ids of the files, ImageKit ids are 24 hexadecimal
+/// characters
+const ID_PREFIX: &str = "64c7a1f2";
+
+/// Characters of the suffix appended to file names to make them unique
+const SUFFIX_CHARACTERS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Length of the suffix appended to file names to make them unique
+const SUFFIX_LENGTH: usize = 9;
+
+const FILE_NOT_FOUND: &str = "The requested file does not exist.";
+const INVALID_FILE_ID: &str = "Your request contains invalid fileId parameter.";
+const MISSING_FILE: &str = "Missing file parameter for upload";
+const MISSING_FILE_NAME: &str = "Missing fileName parameter for upload";
+
+#[derive(Default)]
+struct Store {
+    /// Uploaded files by id, ids are sequential so files are kept in upload
+    /// order
+    files: BTreeMap<String, Response>,
+    sequence: u64,
+}
+
+/// Fake ImageKit client keeping the uploaded files in memory.
+///
+/// Files get ImageKit like ids, unique names, URLs and version info. Image
+/// dimensions are read from JPEG, PNG and GIF files. Failed requests return
+/// the same messages as the ImageKit API.
+pub struct FakeImageKit {
+    url_endpoint: String,
+    store: Mutex<Store>,
+}
+
+impl FakeImageKit {
+    /// Creates a new instance of `FakeImageKit` without files, URLs of the
+    /// uploaded files start with `url_endpoint`
+    pub fn new<T: ToString>(url_endpoint: T) -> Self {
+        Self {
+            url_endpoint: url_endpoint.to_string(),
+            store: Mutex::new(Store::default()),
+        }
+    }
+
+    /// Returns the stored files in upload order
+    pub fn files(&self) -> Vec<Response> {
+        self.store().files.values().cloned().collect()
+    }
+
+    /// Whether a file with the provided id is stored
+    pub fn contains<T: AsRef<str>>(&self, file_id: T) -> bool {
+        self.store().files.contains_key(file_id.as_ref())
+    }
+
+    fn store(&self) -> MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl Upload for FakeImageKit {
+    async fn upload(&self, opts: Options) -> Result<Response> {
+        if opts.file_name.is_empty() {
+            bail!(MISSING_FILE_NAME);
+        }
+
+        let data = opts.file.into_body().await?.collect().await?;
+
+        if data.is_empty() {
+            bail!(MISSING_FILE);
+        }
+
+        let mut store = self.store();
+
+        store.sequence += 1;
+
+        let file_id = format!("{ID_PREFIX}{:016x}", store.sequence);
+        let name = unique_name(&opts.file_name, store.sequence);
+        let url = format!("{}/{name}", self.url_endpoint.trim_end_matches('/'));
+        let dimensions = dimensions(&data);
+        let response = Response {
+            file_id: file_id.clone(),
+            size: data.len() as u64,
+            version_info: VersionInfo {
+                id: file_id.clone(),
+                name: "Version 1".to_string(),
+            },
+            file_path: format!("/{name}"),
+            file_type: match dimensions {
+                Some(_) => FileType::Image,
+                None => FileType::NonImage,
+            },
+            height: dimensions.map(|(_, height)| height),
+            width: dimensions.map(|(width, _)| width),
+            thumbnail_url: dimensions.map(|_| {
+                format!(
+                    "{}/tr:n-ik_ml_thumbnail/{name}",
+                    self.url_endpoint.trim_end_matches('/')
+                )
+            }),
+            ai_tags: None,
+            metadata: None,
+            name,
+            url,
+        };
+
+        store.files.insert(file_id, response.clone());
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Delete for FakeImageKit {
+    async fn delete<T: ToString + Send>(&self, file_id: T) -> Result<()> {
+        let file_id = file_id.to_string();
+
+        validate_file_id(&file_id)?;
+
+        match self.store().files.remove(&file_id) {
+            Some(_) => Ok(()),
+            None => bail!(FILE_NOT_FOUND),
+        }
+    }
+}
+
+#[async_trait]
+impl Details for FakeImageKit {
+    async fn get_file_details<T: AsRef<str> + Display + Send>(&self, id: T) -> Result<Response> {
+        validate_file_id(id.as_ref())?;
+
+        match self.store().files.get(id.as_ref()) {
+            Some(file) => Ok(file.clone()),
+            None => bail!(FILE_NOT_FOUND),
+        }
+    }
+}
+
+impl Url for FakeImageKit {
+    fn url(&self, opts: UrlOptions) -> Result<String> {
+        generate_url(&self.url_endpoint, opts)
+    }
+}
+
+fn validate_file_id(file_id: &str) -> Result<()> {
+    if file_id.len() != 24 || !file_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!(INVALID_FILE_ID);
+    }
+
+    Ok(())
+}
+
+/// Appends a suffix to the name of the file before its extension, as
+/// ImageKit does with `useUniqueFileName`, e.g. `ferris_cTgKr8mAO.jpeg`
+fn unique_name(file_name: &str, sequence: u64) -> String {
+    let (stem, extension) = match file_name.rfind('.') {
+        Some(index) if index > 0 => file_name.split_at(index),
+        _ => (file_name, ""),
+    };
+    // SplitMix64, so consecutive uploads get unrelated suffixes
+    let mut state = sequence.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    state = (state ^ (state >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    state = (state ^ (state >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    state ^= state >> 31;
+
+    let suffix: String = (0..SUFFIX_LENGTH)
+        .map(|_| {
+            let c = SUFFIX_CHARACTERS[(state % SUFFIX_CHARACTERS.len() as u64) as usize];
+            state /= SUFFIX_CHARACTERS.len() as u64;
+            c as char
+        })
+        .collect();
+
+    format!("{stem}_{suffix}{extension}")
+}
+
+/// Reads the width and height of JPEG, PNG and GIF images
+fn dimensions(data: &[u8]) -> Option<(u64, u64)> {
+    let u16_be = |index: usize| -> Option<u64> {
+        let bytes = data.get(index..index + 2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]) as u64)
+    };
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let width = data.get(16..20)?;
+        let height = data.get(20..24)?;
+
+        return Some((
+            u32::from_be_bytes([width[0], width[1], width[2], width[3]]) as u64,
+            u32::from_be_bytes([height[0], height[1], height[2], height[3]]) as u64,
+        ));
+    }
+
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        let size = data.get(6..10)?;
+
+        return Some((
+            u16::from_le_bytes([size[0], size[1]]) as u64,
+            u16::from_le_bytes([size[2], size[3]]) as u64,
+        ));
+    }
+
+    if data.starts_with(&[0xff, 0xd8]) {
+        let mut index = 2;
+
+        // Walks the segments until a start of frame marker, which holds
+        // the dimensions of the image
+        while index + 4 <= data.len() {
+            if data[index] != 0xff {
+                return None;
+            }
+
+            let marker = data[index + 1];
+            let length = u16_be(index + 2)? as usize;
+
+            if matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+                return Some((u16_be(index + 7)?, u16_be(index + 5)?));
+            }
+
+            index += 2 + length;
+        }
+    }
+
+    None
+}
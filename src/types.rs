@@ -1,4 +1,7 @@
-use serde::Deserialize;
+use std::fmt;
+
+use serde::{Deserialize, Serializer};
+use zeroize::Zeroize;
 
 /// Response struct returned on failed requests
 #[derive(Debug, Deserialize)]
@@ -6,3 +9,77 @@ use serde::Deserialize;
 pub struct ErrorResponse {
     pub message: String,
 }
+
+/// Sensitive value, such as the private key or the credentials of an
+/// origin. Redacted when formatted with `Debug` or `Display` and zeroed in
+/// memory when dropped.
+///
+/// It does not implement `Serialize`, so it can not be leaked by logging a
+/// serialized value; the payloads sent to the API expose it explicitly.
+#[derive(Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new<T: Into<String>>(val: T) -> Self {
+        Secret(val.into())
+    }
+
+    /// Returns the underlying value
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret([REDACTED])")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+/// Compares in constant time for values of the same length
+impl PartialEq for Secret {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.0.as_bytes(), other.0.as_bytes());
+
+        a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+impl Eq for Secret {}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(val: &str) -> Self {
+        Secret::new(val)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(val: String) -> Self {
+        Secret::new(val)
+    }
+}
+
+/// Serializes the value of an optional `Secret`, for the fields of payloads
+/// sent to the API
+pub(crate) fn expose_secret<S: Serializer>(
+    secret: &Option<Secret>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match secret {
+        Some(secret) => serializer.serialize_some(secret.expose()),
+        None => serializer.serialize_none(),
+    }
+}
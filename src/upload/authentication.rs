@@ -0,0 +1,96 @@
+use anyhow::{bail, Result};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+
+use crate::ImageKit;
+
+/// Seconds until the parameters generated by
+/// `generate_authentication_parameters` expire
+pub const DEFAULT_EXPIRE: u64 = 30 * 60;
+
+/// ImageKit rejects parameters expiring more than an hour in the future
+pub const MAX_EXPIRE: u64 = 60 * 60;
+
+/// Parameters authenticating an upload from a client holding only the
+/// public key. They are minted by a backend holding the private key and
+/// can be sent to the client as JSON.
+///
+/// Refer: https://docs.imagekit.io/api-reference/upload-file-api/client-side-file-upload#signature-generation-for-client-side-file-upload
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AuthenticationParameters {
+    /// Unique value, a token can only be used for a single upload
+    pub token: String,
+    /// Unix time in seconds when the parameters expire
+    pub expire: u64,
+    /// HMAC-SHA1 of the token and expire, keyed with the private key
+    pub signature: String,
+}
+
+impl ImageKit {
+    /// Mints the parameters for a client side upload with the provided
+    /// unique `token`, expiring at `expire` (Unix time in seconds).
+    ///
+    /// `expire` must be in the future and at most `MAX_EXPIRE` seconds
+    /// ahead, it is not checked on `wasm32` targets which have no clock.
+    pub fn authentication_parameters<T: Into<String>>(
+        &self,
+        token: T,
+        expire: u64,
+    ) -> Result<AuthenticationParameters> {
+        let Some(private_key) = &self.private_key else {
+            bail!("The private key is required to mint authentication parameters");
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        validate_expire(expire, now()?)?;
+
+        let token = token.into();
+        let signature = signature(private_key.expose(), &token, expire)?;
+
+        Ok(AuthenticationParameters {
+            token,
+            expire,
+            signature,
+        })
+    }
+
+    /// Mints the parameters for a client side upload with a random token,
+    /// expiring in `DEFAULT_EXPIRE` seconds
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn generate_authentication_parameters(&self) -> Result<AuthenticationParameters> {
+        self.authentication_parameters(uuid::Uuid::new_v4().to_string(), now()? + DEFAULT_EXPIRE)
+    }
+}
+
+/// HMAC-SHA1 of the token and expire keyed with the private key, hex encoded
+pub(crate) fn signature(private_key: &str, token: &str, expire: u64) -> Result<String> {
+    let mut mac = Hmac::<Sha1>::new_from_slice(private_key.as_bytes())?;
+
+    mac.update(token.as_bytes());
+    mac.update(expire.to_string().as_bytes());
+
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Checks `expire` is after `now` and at most `MAX_EXPIRE` seconds ahead
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn validate_expire(expire: u64, now: u64) -> Result<()> {
+    if expire <= now {
+        bail!("Authentication parameters must expire in the future, got {expire}");
+    }
+
+    if expire > now + MAX_EXPIRE {
+        bail!("Authentication parameters must expire within {MAX_EXPIRE} seconds, got {expire}");
+    }
+
+    Ok(())
+}
+
+/// Current Unix time in seconds
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> Result<u64> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
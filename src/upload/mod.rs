@@ -1,27 +1,60 @@
+pub mod authentication;
 pub mod types;
 
-use std::io::Cursor;
-
-use anyhow::{bail, Result};
+use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::multipart::{Form, Part};
-use reqwest::{Body, StatusCode};
+#[cfg(feature = "tokio")]
+use bytes::BytesMut;
+#[cfg(feature = "tokio")]
+use futures_util::StreamExt;
+#[cfg(feature = "tokio")]
 use tokio::fs::File;
-use tokio::io::BufReader;
+#[cfg(feature = "tokio")]
+use tokio::io::AsyncSeekExt;
+#[cfg(feature = "tokio")]
 use tokio_util::codec::{BytesCodec, FramedRead};
 
-use crate::{ErrorResponse, ImageKit};
+use crate::client::parse_response;
+use crate::http::{Body, Form, HttpRequest, Method};
+use crate::metrics::Operation;
+use crate::ImageKit;
 
-use self::types::Response;
+pub use self::authentication::AuthenticationParameters;
+use self::types::{Response, ResponseField};
 
 /// Default Upload Endpoint used by ImageKit
 pub const UPLOAD_ENDPOINT: &str = "https://upload.imagekit.io/api/v1/files/upload";
 
 pub enum UploadFile {
+    /// File streamed from its current position when uploading, available
+    /// with the `tokio` feature
+    #[cfg(feature = "tokio")]
     Binary(File),
     Bytes(Vec<u8>),
 }
 
+impl UploadFile {
+    /// Body of the file part of the upload, files are streamed while the
+    /// request is sent instead of being read in memory
+    pub(crate) async fn into_body(self) -> Result<Body> {
+        match self {
+            #[cfg(feature = "tokio")]
+            UploadFile::Binary(mut file) => {
+                let length = file.metadata().await?.len() - file.stream_position().await?;
+                let stream = FramedRead::new(file, BytesCodec::new())
+                    .map(|chunk| chunk.map(BytesMut::freeze));
+
+                Ok(Body::Stream {
+                    stream: Box::pin(stream),
+                    length,
+                })
+            }
+            UploadFile::Bytes(file_bytes) => Ok(Body::Bytes(file_bytes)),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
 impl From<File> for UploadFile {
     fn from(file: File) -> Self {
         UploadFile::Binary(file)
@@ -36,46 +69,67 @@ impl From<Vec<u8>> for UploadFile {
 
 /// Options sent to the server as part of the `Form` when uploding a file.
 ///
+/// The file is an `UploadFile`, or an `imagekit::blocking::UploadFile` for
+/// the blocking client.
+///
 /// Refer: https://docs.imagekit.io/api-reference/upload-file-api/server-side-file-upload#request-structure-multipart-form-data
-pub struct Options {
-    /// Upload Endpoint to use, by default:
-    /// `https://upload.imagekit.io/api/v1/files/upload`
-    /// is used.
-    endpoint: String,
+pub struct Options<F = UploadFile> {
+    /// Upload Endpoint to use, by default the `upload_endpoint` of the
+    /// client, `https://upload.imagekit.io/api/v1/files/upload`, is used.
+    pub(crate) endpoint: Option<String>,
     /// File to upload
-    file: UploadFile,
+    pub(crate) file: F,
     /// Name to set to the file being uploaded
     ///
     /// The filename must only have alphanumeric characters (a-z, A-Z and/or 0-9),
     /// allowed symbols include `.`, `_`, and `-`.
-    file_name: String,
+    pub(crate) file_name: String,
+    /// Parameters minted by a backend, sent instead of the private key
+    pub(crate) authentication: Option<AuthenticationParameters>,
+    /// Optional fields to include in the response
+    pub(crate) response_fields: Vec<ResponseField>,
 }
 
-impl Options {
+impl<F> Options<F> {
     /// Creates a new instance of `Options` with the provided `UploadFile` and
     /// file name.
-    pub fn new<T: ToString>(file: UploadFile, file_name: T) -> Self {
+    pub fn new<T: ToString>(file: F, file_name: T) -> Self {
         Self {
+            endpoint: None,
             file,
             file_name: file_name.to_string(),
-            ..Default::default()
+            authentication: None,
+            response_fields: Vec::new(),
         }
     }
 
     /// Sets the endpoint to use when uploading the file.
     pub fn endpoint<T: AsRef<str> + Into<String>>(mut self, endpoint: T) -> Self {
-        self.endpoint = endpoint.into();
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Authenticates the upload with the public key and the provided
+    /// parameters instead of the private key, for clients which must not
+    /// hold it.
+    ///
+    /// Refer: https://docs.imagekit.io/api-reference/upload-file-api/client-side-file-upload
+    pub fn authentication_parameters(mut self, val: AuthenticationParameters) -> Self {
+        self.authentication = Some(val);
+        self
+    }
+
+    /// Sets the optional fields included in the response, e.g.
+    /// `ResponseField::Metadata` for the pHash read by `phash::Perceptual`
+    pub fn response_fields(mut self, val: Vec<ResponseField>) -> Self {
+        self.response_fields = val;
         self
     }
 }
 
-impl Default for Options {
+impl<F: From<Vec<u8>>> Default for Options<F> {
     fn default() -> Self {
-        Self {
-            endpoint: UPLOAD_ENDPOINT.to_string(),
-            file: UploadFile::Bytes(vec![]),
-            file_name: "untitled".to_string(),
-        }
+        Self::new(F::from(vec![]), "untitled")
     }
 }
 
@@ -87,49 +141,74 @@ pub trait Upload {
 
 #[async_trait]
 impl Upload for ImageKit {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "imagekit.upload",
+            skip_all,
+            fields(file_name = %opts.file_name, bytes = tracing::field::Empty),
+            err
+        )
+    )]
     async fn upload(&self, opts: Options) -> Result<Response> {
-        let mut form = Form::new();
-
-        form = form.text("fileName", opts.file_name.clone());
-        match opts.file {
-            UploadFile::Binary(file) => {
-                let stream = FramedRead::new(file, BytesCodec::new());
-                let file_body = Body::wrap_stream(stream);
-                let form_file = Part::stream(file_body)
-                    .file_name(opts.file_name)
-                    .mime_str("image/jpeg")
-                    .unwrap();
-                form = form.part("file", form_file);
+        let data = opts.file.into_body().await?;
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("bytes", data.len());
+
+        let endpoint = opts.endpoint.as_deref().unwrap_or(&self.upload_endpoint);
+        let response = match &opts.authentication {
+            Some(params) => {
+                let client = Some((self.public_key.as_str(), params));
+                let request = request(
+                    endpoint,
+                    &opts.file_name,
+                    data,
+                    &opts.response_fields,
+                    client,
+                );
+
+                self.dispatch(Operation::Upload, request).await?
             }
-            UploadFile::Bytes(file_bytes) => {
-                let cursor = Cursor::new(file_bytes);
-                let buf_reader = BufReader::new(cursor);
-                let stream = FramedRead::new(buf_reader, BytesCodec::new());
-                let file_body = Body::wrap_stream(stream);
-                let form_file = Part::stream(file_body)
-                    .file_name(opts.file_name)
-                    .mime_str("image/jpeg")
-                    .unwrap();
-                form = form.part("file", form_file);
+            None => {
+                self.send(
+                    Operation::Upload,
+                    request(endpoint, &opts.file_name, data, &opts.response_fields, None),
+                )
+                .await?
             }
-        }
+        };
 
-        let response = self
-            .client
-            .post(opts.endpoint)
-            .multipart(form)
-            .send()
-            .await
-            .unwrap();
+        parse_response(&response, 200)
+    }
+}
 
-        if matches!(response.status(), StatusCode::OK) {
-            let result = response.json::<Response>().await.unwrap();
+/// Builds the multipart upload request. Client side uploads provide the
+/// public key and the authentication parameters.
+pub(crate) fn request(
+    endpoint: &str,
+    file_name: &str,
+    data: Body,
+    response_fields: &[ResponseField],
+    client: Option<(&str, &AuthenticationParameters)>,
+) -> HttpRequest {
+    let mut form = Form::new().text("fileName", file_name);
+
+    if !response_fields.is_empty() {
+        let fields: Vec<String> = response_fields.iter().map(ToString::to_string).collect();
+
+        form = form.text("responseFields", fields.join(","));
+    }
 
-            return Ok(result);
-        }
+    if let Some((public_key, params)) = client {
+        form = form
+            .text("publicKey", public_key)
+            .text("token", &params.token)
+            .text("expire", params.expire.to_string())
+            .text("signature", &params.signature);
+    }
 
-        let result = response.json::<ErrorResponse>().await.unwrap();
+    let form = form.file("file", file_name, "image/jpeg", data);
 
-        bail!(result.message);
-    }
+    HttpRequest::new(Method::Post, endpoint).multipart(form)
 }
@@ -1,7 +1,9 @@
+use std::fmt::{self, Display};
+
 use serde::Deserialize;
 
 /// An object containing the file or file version's id (versionId) and name.
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VersionInfo {
     pub id: String,
@@ -11,7 +13,7 @@ pub struct VersionInfo {
 /// Array of AITags associated with the image. If no AITags are set, it
 /// will be null. These tags can be added using the google-auto-tagging
 /// or aws-auto-tagging extensions.
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AiTag {
     pub name: String,
@@ -20,7 +22,7 @@ pub struct AiTag {
 }
 
 /// The type of file could be either `image` or `non-image`.
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 pub enum FileType {
     #[serde(rename = "image")]
     Image,
@@ -28,11 +30,67 @@ pub enum FileType {
     NonImage,
 }
 
+/// Fields which are only included in the upload response when requested,
+/// e.g. `Metadata` to read the pHash of the image.
+///
+/// Refer: https://docs.imagekit.io/api-reference/upload-file-api/server-side-file-upload#request-structure-multipart-form-data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResponseField {
+    Tags,
+    CustomCoordinates,
+    IsPrivateFile,
+    EmbeddedMetadata,
+    CustomMetadata,
+    Metadata,
+}
+
+impl Display for ResponseField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = match self {
+            ResponseField::Tags => "tags",
+            ResponseField::CustomCoordinates => "customCoordinates",
+            ResponseField::IsPrivateFile => "isPrivateFile",
+            ResponseField::EmbeddedMetadata => "embeddedMetadata",
+            ResponseField::CustomMetadata => "customMetadata",
+            ResponseField::Metadata => "metadata",
+        };
+
+        write!(f, "{field}")
+    }
+}
+
+/// Image metadata as returned by ImageKit when `metadata` is requested as
+/// part of the response fields.
+///
+/// Refer: https://docs.imagekit.io/api-reference/metadata-api
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    /// Height of the image in pixels
+    pub height: Option<u64>,
+    /// Width of the image in pixels
+    pub width: Option<u64>,
+    /// Size of the image file in Bytes
+    pub size: Option<u64>,
+    /// The format of the file (e.g. `jpg`, `png`)
+    pub format: Option<String>,
+    /// Whether the image has an embedded color profile
+    pub has_color_profile: Option<bool>,
+    /// Quality of the image, only for JPEG images
+    pub quality: Option<u8>,
+    /// Density of the image in DPI
+    pub density: Option<u32>,
+    /// Whether the image has an alpha channel
+    pub has_transparency: Option<bool>,
+    /// Perceptual hash of the image as 16 hexadecimal characters
+    pub p_hash: Option<String>,
+}
+
 /// Response struct returned from successful requests to the ImageKit API.
 ///
 /// Refer: https://docs.imagekit.io/api-reference/upload-file-api/server-side-file-upload#response-code-and-structure-json
 /// Fields Documentation: https://docs.imagekit.io/api-reference/upload-file-api/server-side-file-upload#understanding-response
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     /// Unique fileId. Store this fileld in your database, as this will be used
@@ -65,4 +123,7 @@ pub struct Response {
     /// will be null. These tags can be added using the google-auto-tagging
     /// or aws-auto-tagging extensions.
     pub ai_tags: Option<Vec<AiTag>>,
+    /// Image metadata, only present when `ResponseField::Metadata` is
+    /// requested
+    pub metadata: Option<Metadata>,
 }
@@ -0,0 +1,206 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+
+use super::types::{decode_base64, effect_params, encode_base64};
+
+/// Realistic shadow added under the subject of an image with a transparent
+/// background (`e-dropshadow`). Fields left unset use the ImageKit
+/// defaults.
+///
+/// Refer: https://docs.imagekit.io/features/ai-transformations#ai-drop-shadow-e-dropshadow
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DropShadow {
+    azimuth: Option<u32>,
+    elevation: Option<u32>,
+    saturation: Option<u32>,
+}
+
+impl DropShadow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the direction of the light source in degrees, between `0` and
+    /// `360`
+    pub fn azimuth(mut self, val: u32) -> Self {
+        self.azimuth = Some(val);
+        self
+    }
+
+    /// Sets the elevation of the light source in degrees, between `0` and
+    /// `90`
+    pub fn elevation(mut self, val: u32) -> Self {
+        self.elevation = Some(val);
+        self
+    }
+
+    /// Sets the saturation of the shadow, between `0` and `100`
+    pub fn saturation(mut self, val: u32) -> Self {
+        self.saturation = Some(val);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if matches!(self.azimuth, Some(azimuth) if azimuth > 360) {
+            bail!("Drop shadow azimuth must be between 0 and 360");
+        }
+
+        if matches!(self.elevation, Some(elevation) if elevation > 90) {
+            bail!("Drop shadow elevation must be between 0 and 90");
+        }
+
+        if matches!(self.saturation, Some(saturation) if saturation > 100) {
+            bail!("Drop shadow saturation must be between 0 and 100");
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DropShadow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut params: Vec<String> = Vec::new();
+
+        if let Some(azimuth) = self.azimuth {
+            params.push(format!("az-{azimuth}"));
+        }
+
+        if let Some(elevation) = self.elevation {
+            params.push(format!("el-{elevation}"));
+        }
+
+        if let Some(saturation) = self.saturation {
+            params.push(format!("st-{saturation}"));
+        }
+
+        if params.is_empty() {
+            write!(f, "dropshadow")
+        } else {
+            write!(f, "dropshadow-{}", params.join("_"))
+        }
+    }
+}
+
+impl FromStr for DropShadow {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut drop_shadow = DropShadow::new();
+
+        for (key, value) in effect_params(s, "dropshadow")? {
+            match key {
+                "az" => drop_shadow.azimuth = Some(value.parse()?),
+                "el" => drop_shadow.elevation = Some(value.parse()?),
+                "st" => drop_shadow.saturation = Some(value.parse()?),
+                _ => bail!("Invalid drop shadow \"{s}\""),
+            }
+        }
+
+        Ok(drop_shadow)
+    }
+}
+
+/// AI powered transformations.
+///
+/// Refer: https://docs.imagekit.io/features/ai-transformations
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AiEffect {
+    /// `e-bgremove`, removes the background using ImageKit's model
+    BackgroundRemoval,
+    /// `e-removedotbg`, removes the background using remove.bg
+    RemoveDotBg,
+    /// `e-changebg`, replaces the background with one described by the
+    /// prompt
+    ChangeBackground(String),
+    /// `e-edit`, edits the image as described by the prompt
+    Edit(String),
+    /// `e-dropshadow`, adds a shadow under the subject of the image
+    DropShadow(DropShadow),
+    /// `e-genvar`, generates a variation of the image
+    GenerativeVariation,
+    /// `e-retouch`, improves the quality of the image
+    Retouch,
+    /// `e-upscale`, increases the resolution of the image
+    Upscale,
+    /// `bg-genfill`, extends the image when padding, optionally guided by a
+    /// prompt. ImageKit exposes generative fill as a background rather than
+    /// an `e-` effect, so it must be used along with `CropMode::PadResize`.
+    GenerativeFill(Option<String>),
+}
+
+impl AiEffect {
+    /// Parses an AI transformation from its `key` (`e` or `bg`) and `value`
+    pub(crate) fn parse(key: &str, value: &str) -> Result<AiEffect> {
+        let effect = match (key, value) {
+            ("e", "bgremove") => AiEffect::BackgroundRemoval,
+            ("e", "removedotbg") => AiEffect::RemoveDotBg,
+            ("e", "genvar") => AiEffect::GenerativeVariation,
+            ("e", "retouch") => AiEffect::Retouch,
+            ("e", "upscale") => AiEffect::Upscale,
+            ("e", value) if value.starts_with("dropshadow") => AiEffect::DropShadow(value.parse()?),
+            ("e", value) => match value.split_once('-') {
+                Some(("changebg", prompt)) => AiEffect::ChangeBackground(parse_prompt(prompt)?),
+                Some(("edit", prompt)) => AiEffect::Edit(parse_prompt(prompt)?),
+                _ => bail!("Unsupported AI transformation \"e-{value}\""),
+            },
+            ("bg", "genfill") => AiEffect::GenerativeFill(None),
+            ("bg", value) => match value.strip_prefix("genfill-") {
+                Some(prompt) => AiEffect::GenerativeFill(Some(parse_prompt(prompt)?)),
+                None => bail!("Unsupported AI transformation \"bg-{value}\""),
+            },
+            _ => bail!("Unsupported AI transformation \"{key}-{value}\""),
+        };
+
+        Ok(effect)
+    }
+
+    pub(crate) fn param(&self) -> Result<String> {
+        let param = match self {
+            AiEffect::BackgroundRemoval => "e-bgremove".to_string(),
+            AiEffect::RemoveDotBg => "e-removedotbg".to_string(),
+            AiEffect::ChangeBackground(prompt) => format!("e-changebg-{}", prompt_param(prompt)?),
+            AiEffect::Edit(prompt) => format!("e-edit-{}", prompt_param(prompt)?),
+            AiEffect::DropShadow(drop_shadow) => {
+                drop_shadow.validate()?;
+                format!("e-{drop_shadow}")
+            }
+            AiEffect::GenerativeVariation => "e-genvar".to_string(),
+            AiEffect::Retouch => "e-retouch".to_string(),
+            AiEffect::Upscale => "e-upscale".to_string(),
+            AiEffect::GenerativeFill(None) => "bg-genfill".to_string(),
+            AiEffect::GenerativeFill(Some(prompt)) => {
+                format!("bg-genfill-{}", prompt_param(prompt)?)
+            }
+        };
+
+        Ok(param)
+    }
+}
+
+/// Prompts made of alphanumeric characters are sent as they are
+/// (`prompt-`), any other prompt is sent Base64 encoded (`prompte-`) so it
+/// cannot break the transformation string.
+fn prompt_param(prompt: &str) -> Result<String> {
+    if prompt.trim().is_empty() {
+        bail!("AI transformation prompts must not be empty");
+    }
+
+    if prompt.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Ok(format!("prompt-{prompt}"));
+    }
+
+    Ok(format!("prompte-{}", encode_base64(prompt)))
+}
+
+fn parse_prompt(param: &str) -> Result<String> {
+    if let Some(prompt) = param.strip_prefix("prompte-") {
+        return decode_base64(prompt);
+    }
+
+    match param.strip_prefix("prompt-") {
+        Some(prompt) => Ok(prompt.to_string()),
+        None => bail!("Invalid prompt \"{param}\""),
+    }
+}
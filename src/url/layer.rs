@@ -0,0 +1,591 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+
+use super::transformation::Transformation;
+use super::types::{decode_base64, encode_base64, parse_signed, signed, validate_color};
+
+/// Anchor point of a layer relative to its parent image (`lfo-`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/overlay-using-layers#position-of-layer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerFocus {
+    Center,
+    Top,
+    Left,
+    Bottom,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Display for LayerFocus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerFocus::Center => write!(f, "center"),
+            LayerFocus::Top => write!(f, "top"),
+            LayerFocus::Left => write!(f, "left"),
+            LayerFocus::Bottom => write!(f, "bottom"),
+            LayerFocus::Right => write!(f, "right"),
+            LayerFocus::TopLeft => write!(f, "top_left"),
+            LayerFocus::TopRight => write!(f, "top_right"),
+            LayerFocus::BottomLeft => write!(f, "bottom_left"),
+            LayerFocus::BottomRight => write!(f, "bottom_right"),
+        }
+    }
+}
+
+impl FromStr for LayerFocus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "center" => Ok(LayerFocus::Center),
+            "top" => Ok(LayerFocus::Top),
+            "left" => Ok(LayerFocus::Left),
+            "bottom" => Ok(LayerFocus::Bottom),
+            "right" => Ok(LayerFocus::Right),
+            "top_left" => Ok(LayerFocus::TopLeft),
+            "top_right" => Ok(LayerFocus::TopRight),
+            "bottom_left" => Ok(LayerFocus::BottomLeft),
+            "bottom_right" => Ok(LayerFocus::BottomRight),
+            _ => bail!("Invalid layer focus \"{s}\""),
+        }
+    }
+}
+
+/// Position of a layer, either as coordinates from the top-left corner of
+/// the parent image, as an anchor point, or both.
+///
+/// Negative coordinates are relative to the bottom-right corner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LayerPosition {
+    x: Option<i32>,
+    y: Option<i32>,
+    focus: Option<LayerFocus>,
+}
+
+impl LayerPosition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the horizontal position (`lx-`)
+    pub fn x(mut self, val: i32) -> Self {
+        self.x = Some(val);
+        self
+    }
+
+    /// Sets the vertical position (`ly-`)
+    pub fn y(mut self, val: i32) -> Self {
+        self.y = Some(val);
+        self
+    }
+
+    /// Sets the anchor point (`lfo-`)
+    pub fn focus(mut self, val: LayerFocus) -> Self {
+        self.focus = Some(val);
+        self
+    }
+
+    /// Applies a position parameter, returning `false` if `key` is not a
+    /// position parameter
+    fn set(&mut self, key: &str, value: &str) -> Result<bool> {
+        match key {
+            "lx" => self.x = Some(parse_signed(value)?),
+            "ly" => self.y = Some(parse_signed(value)?),
+            "lfo" => self.focus = Some(value.parse()?),
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn params(&self) -> Vec<String> {
+        let mut params: Vec<String> = Vec::new();
+
+        if let Some(x) = self.x {
+            params.push(format!("lx-{}", signed(x)));
+        }
+
+        if let Some(y) = self.y {
+            params.push(format!("ly-{}", signed(y)));
+        }
+
+        if let Some(focus) = &self.focus {
+            params.push(format!("lfo-{focus}"));
+        }
+
+        params
+    }
+}
+
+/// Image placed on top of the parent image (`l-image`), transformations
+/// applied to the layer can include nested layers.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/overlay-using-layers#image-layer
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageLayer {
+    path: String,
+    transformation: Box<Transformation>,
+    position: LayerPosition,
+}
+
+impl ImageLayer {
+    /// Creates an image layer from the path of an image in the media
+    /// library, e.g. `logos/white.png`
+    pub fn new<T: AsRef<str> + Into<String>>(path: T) -> Self {
+        Self {
+            path: path.into(),
+            transformation: Box::default(),
+            position: LayerPosition::new(),
+        }
+    }
+
+    /// Sets the transformation applied to the layer image
+    pub fn transformation(mut self, val: Transformation) -> Self {
+        self.transformation = Box::new(val);
+        self
+    }
+
+    /// Sets the position of the layer
+    pub fn position(mut self, val: LayerPosition) -> Self {
+        self.position = val;
+        self
+    }
+
+    fn params(&self) -> Result<Vec<String>> {
+        let path = self.path.trim_matches('/');
+
+        if path.is_empty() {
+            bail!("Image layers require a path");
+        }
+
+        let is_plain = path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'));
+        let input = if is_plain {
+            format!("i-{}", path.replace('/', "@@"))
+        } else {
+            format!("ie-{}", encode_base64(path))
+        };
+
+        let mut params = vec!["l-image".to_string(), input];
+
+        params.extend(self.transformation.params()?);
+        params.extend(self.position.params());
+        params.push("l-end".into());
+
+        Ok(params)
+    }
+}
+
+/// Font style of a text layer (`tg-`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Typography {
+    Bold,
+    Italic,
+    BoldItalic,
+}
+
+impl Display for Typography {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Typography::Bold => write!(f, "b"),
+            Typography::Italic => write!(f, "i"),
+            Typography::BoldItalic => write!(f, "b_i"),
+        }
+    }
+}
+
+impl FromStr for Typography {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "b" => Ok(Typography::Bold),
+            "i" => Ok(Typography::Italic),
+            "b_i" | "i_b" => Ok(Typography::BoldItalic),
+            _ => bail!("Invalid typography \"{s}\""),
+        }
+    }
+}
+
+/// Padding around the text of a text layer (`pa-`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    /// Same padding for every side
+    All(u32),
+    /// Padding for each side in the order top, right, bottom and left
+    Sides(u32, u32, u32, u32),
+}
+
+impl Display for Padding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Padding::All(val) => write!(f, "{val}"),
+            Padding::Sides(top, right, bottom, left) => {
+                write!(f, "{top}_{right}_{bottom}_{left}")
+            }
+        }
+    }
+}
+
+impl FromStr for Padding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let values = s
+            .split('_')
+            .map(str::parse::<u32>)
+            .collect::<std::result::Result<Vec<u32>, _>>()?;
+
+        match values.as_slice() {
+            [all] => Ok(Padding::All(*all)),
+            [top, right, bottom, left] => Ok(Padding::Sides(*top, *right, *bottom, *left)),
+            _ => bail!("Invalid padding \"{s}\""),
+        }
+    }
+}
+
+/// Text placed on top of the parent image (`l-text`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/overlay-using-layers#text-layer
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextLayer {
+    text: String,
+    font_family: Option<String>,
+    font_size: Option<u32>,
+    font_color: Option<String>,
+    background: Option<String>,
+    padding: Option<Padding>,
+    typography: Option<Typography>,
+    width: Option<u32>,
+    position: LayerPosition,
+}
+
+impl TextLayer {
+    pub fn new<T: AsRef<str> + Into<String>>(text: T) -> Self {
+        Self {
+            text: text.into(),
+            font_family: None,
+            font_size: None,
+            font_color: None,
+            background: None,
+            padding: None,
+            typography: None,
+            width: None,
+            position: LayerPosition::new(),
+        }
+    }
+
+    /// Sets the font family (`ff-`), either a supported font name or the
+    /// path of a custom font in the media library
+    pub fn font_family<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.font_family = Some(val.into());
+        self
+    }
+
+    /// Sets the font size in pixels (`fs-`)
+    pub fn font_size(mut self, val: u32) -> Self {
+        self.font_size = Some(val);
+        self
+    }
+
+    /// Sets the font color (`co-`)
+    pub fn font_color<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.font_color = Some(val.into());
+        self
+    }
+
+    /// Sets the background color behind the text (`bg-`)
+    pub fn background<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.background = Some(val.into());
+        self
+    }
+
+    /// Sets the padding around the text (`pa-`)
+    pub fn padding(mut self, val: Padding) -> Self {
+        self.padding = Some(val);
+        self
+    }
+
+    /// Sets the font style (`tg-`)
+    pub fn typography(mut self, val: Typography) -> Self {
+        self.typography = Some(val);
+        self
+    }
+
+    /// Sets the maximum width of the text box (`w-`), wrapping the text
+    pub fn width(mut self, val: u32) -> Self {
+        self.width = Some(val);
+        self
+    }
+
+    /// Sets the position of the layer
+    pub fn position(mut self, val: LayerPosition) -> Self {
+        self.position = val;
+        self
+    }
+
+    fn params(&self) -> Result<Vec<String>> {
+        if self.text.is_empty() {
+            bail!("Text layers require a text");
+        }
+
+        let input = if self.text.chars().all(|c| c.is_ascii_alphanumeric()) {
+            format!("i-{}", self.text)
+        } else {
+            format!("ie-{}", encode_base64(&self.text))
+        };
+
+        let mut params = vec!["l-text".to_string(), input];
+
+        if let Some(width) = self.width {
+            params.push(format!("w-{width}"));
+        }
+
+        if let Some(font_family) = &self.font_family {
+            let is_valid = font_family
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@'));
+
+            if font_family.is_empty() || !is_valid {
+                bail!("Invalid font family \"{font_family}\"");
+            }
+
+            params.push(format!("ff-{font_family}"));
+        }
+
+        if let Some(font_size) = self.font_size {
+            if font_size == 0 {
+                bail!("Font size must be greater than 0");
+            }
+
+            params.push(format!("fs-{font_size}"));
+        }
+
+        if let Some(font_color) = &self.font_color {
+            validate_color(font_color)?;
+            params.push(format!("co-{font_color}"));
+        }
+
+        if let Some(background) = &self.background {
+            validate_color(background)?;
+            params.push(format!("bg-{background}"));
+        }
+
+        if let Some(padding) = &self.padding {
+            params.push(format!("pa-{padding}"));
+        }
+
+        if let Some(typography) = &self.typography {
+            params.push(format!("tg-{typography}"));
+        }
+
+        params.extend(self.position.params());
+        params.push("l-end".into());
+
+        Ok(params)
+    }
+}
+
+/// Solid color block placed on top of the parent image (`l-solid`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/overlay-using-layers#solid-color-layer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolidLayer {
+    color: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    position: LayerPosition,
+}
+
+impl SolidLayer {
+    pub fn new<T: AsRef<str> + Into<String>>(color: T) -> Self {
+        Self {
+            color: color.into(),
+            width: None,
+            height: None,
+            position: LayerPosition::new(),
+        }
+    }
+
+    /// Sets the width of the block in pixels
+    pub fn width(mut self, val: u32) -> Self {
+        self.width = Some(val);
+        self
+    }
+
+    /// Sets the height of the block in pixels
+    pub fn height(mut self, val: u32) -> Self {
+        self.height = Some(val);
+        self
+    }
+
+    /// Sets the position of the layer
+    pub fn position(mut self, val: LayerPosition) -> Self {
+        self.position = val;
+        self
+    }
+
+    fn params(&self) -> Result<Vec<String>> {
+        validate_color(&self.color)?;
+
+        let mut params = vec!["l-solid".to_string(), format!("co-{}", self.color)];
+
+        if let Some(width) = self.width {
+            params.push(format!("w-{width}"));
+        }
+
+        if let Some(height) = self.height {
+            params.push(format!("h-{height}"));
+        }
+
+        params.extend(self.position.params());
+        params.push("l-end".into());
+
+        Ok(params)
+    }
+}
+
+/// Layer placed on top of an image, used for watermarks and text overlays.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/overlay-using-layers
+#[derive(Clone, Debug, PartialEq)]
+pub enum Layer {
+    Image(ImageLayer),
+    Text(TextLayer),
+    Solid(SolidLayer),
+}
+
+impl Layer {
+    /// Parses the parameters between `l-<kind>` and its matching `l-end`
+    pub(crate) fn parse(kind: &str, tokens: &[&str]) -> Result<Layer> {
+        let mut input: Option<String> = None;
+        let mut position = LayerPosition::new();
+        let mut rest: Vec<&str> = Vec::new();
+        let mut depth = 0;
+
+        for token in tokens {
+            if *token == "l-end" {
+                depth -= 1;
+            } else if token.starts_with("l-") {
+                depth += 1;
+            }
+
+            if depth > 0 || *token == "l-end" {
+                rest.push(token);
+                continue;
+            }
+
+            let (key, value) = token.split_once('-').unwrap_or((token, ""));
+
+            match key {
+                "i" if input.is_none() => input = Some(value.replace("@@", "/")),
+                "ie" if input.is_none() => input = Some(decode_base64(value)?),
+                _ if position.set(key, value)? => {}
+                _ => rest.push(token),
+            }
+        }
+
+        let layer = match kind {
+            "image" => {
+                let Some(path) = input else {
+                    bail!("Image layers require a path");
+                };
+
+                ImageLayer::new(path)
+                    .transformation(Transformation::from_tokens(&rest)?)
+                    .position(position)
+                    .into()
+            }
+            "text" => {
+                let Some(text) = input else {
+                    bail!("Text layers require a text");
+                };
+                let mut layer = TextLayer::new(text).position(position);
+
+                for (key, value) in Layer::pairs(&rest) {
+                    match key {
+                        "w" => layer.width = Some(value.parse()?),
+                        "ff" => layer.font_family = Some(value.to_string()),
+                        "fs" => layer.font_size = Some(value.parse()?),
+                        "co" => layer.font_color = Some(value.to_string()),
+                        "bg" => layer.background = Some(value.to_string()),
+                        "pa" => layer.padding = Some(value.parse()?),
+                        "tg" => layer.typography = Some(value.parse()?),
+                        _ => bail!("Unsupported text layer parameter \"{key}\""),
+                    }
+                }
+
+                layer.into()
+            }
+            "solid" => {
+                let mut color: Option<String> = None;
+                let mut width: Option<u32> = None;
+                let mut height: Option<u32> = None;
+
+                for (key, value) in Layer::pairs(&rest) {
+                    match key {
+                        "co" => color = Some(value.to_string()),
+                        "w" => width = Some(value.parse()?),
+                        "h" => height = Some(value.parse()?),
+                        _ => bail!("Unsupported solid layer parameter \"{key}\""),
+                    }
+                }
+
+                let Some(color) = color else {
+                    bail!("Solid layers require a color");
+                };
+
+                SolidLayer {
+                    color,
+                    width,
+                    height,
+                    position,
+                }
+                .into()
+            }
+            _ => bail!("Unsupported layer \"l-{kind}\""),
+        };
+
+        Ok(layer)
+    }
+
+    fn pairs<'a>(tokens: &[&'a str]) -> Vec<(&'a str, &'a str)> {
+        tokens
+            .iter()
+            .map(|token| token.split_once('-').unwrap_or((token, "")))
+            .collect()
+    }
+
+    pub(crate) fn params(&self) -> Result<Vec<String>> {
+        match self {
+            Layer::Image(layer) => layer.params(),
+            Layer::Text(layer) => layer.params(),
+            Layer::Solid(layer) => layer.params(),
+        }
+    }
+}
+
+impl From<ImageLayer> for Layer {
+    fn from(layer: ImageLayer) -> Self {
+        Layer::Image(layer)
+    }
+}
+
+impl From<TextLayer> for Layer {
+    fn from(layer: TextLayer) -> Self {
+        Layer::Text(layer)
+    }
+}
+
+impl From<SolidLayer> for Layer {
+    fn from(layer: SolidLayer) -> Self {
+        Layer::Solid(layer)
+    }
+}
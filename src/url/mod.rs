@@ -1,60 +1,34 @@
-use std::collections::HashMap;
-
-use anyhow::Result;
-
+pub mod ai;
+pub mod layer;
+pub(crate) mod parse;
+pub mod responsive;
+pub mod transformation;
+pub mod types;
+pub mod video;
+
+use anyhow::{bail, Result};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use url::Url as ParsedUrl;
+
+use crate::http::COMPONENT;
 use crate::ImageKit;
 
-#[derive(Default)]
-pub struct Transformation {
-    width: Option<u32>,
-    height: Option<u32>,
-    aspect_ratio: Option<String>,
-}
-
-impl Transformation {
-    pub fn new() -> Self {
-        Self { ..Self::default() }
-    }
-
-    pub fn width(mut self, val: u32) -> Self {
-        self.width = Some(val);
-        self
-    }
-
-    pub fn height(mut self, val: u32) -> Self {
-        self.height = Some(val);
-        self
-    }
-
-    pub fn aspect_raio<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
-        self.aspect_ratio = Some(val.into());
-        self
-    }
-
-    pub fn transform(&self) -> Result<String> {
-        let mut output = String::default();
-
-        if let Some(width) = self.width {
-            output.push_str(&format!(",w-{width}"))
-        }
-
-        if let Some(height) = self.height {
-            output.push_str(&format!(",h-{height}"))
-        }
-
-        if let Some(aspect_ratio) = &self.aspect_ratio {
-            output.push_str(&format!(",ar-{aspect_ratio}"))
-        }
-
-        if output.is_empty() {
-            return Err(anyhow::anyhow!("No transformation applied"));
-        }
-
-        let output = output.trim_matches(',');
-        Ok(output.into())
-    }
-}
-
+pub use self::ai::{AiEffect, DropShadow};
+pub use self::layer::{
+    ImageLayer, Layer, LayerFocus, LayerPosition, Padding, SolidLayer, TextLayer, Typography,
+};
+pub use self::responsive::{Breakpoints, Picture, Responsive};
+pub use self::transformation::Transformation;
+pub use self::types::{
+    Border, Crop, CropMode, Dimension, Dpr, Flip, Focus, Format, Gradient, Radius, Rotation,
+    Shadow, Sharpen, Trim, UnsharpMask,
+};
+pub use self::video::{AudioCodec, Protocol, VideoCodec};
+
+/// Name of the query parameter identifying the SDK generating the URL
+pub(crate) const SDK_VERSION_PARAMETER: &str = "ik-sdk-version";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TransformationPosition {
     Path,
     Query,
@@ -69,28 +43,49 @@ impl TransformationPosition {
 /// Options for generating the url.
 ///
 /// Refer: https://docs.imagekit.io/features/image-transformations
+#[derive(Clone)]
 pub struct Options {
     /// URL endpoint for this particular image
     /// By default the url used in sdk initialization is used
     url_endpoint: Option<String>,
     path: Option<String>,
     src: Option<String>,
-    transformation: Transformation,
+    /// transformation steps, chained in the same order
+    transformations: Vec<Transformation>,
     /// position for url transformation. i.e., query or path
     transformation_position: TransformationPosition,
-    /// any other query parameters that need to be added to the URL
-    query_parameters: Option<HashMap<String, String>>,
+    /// any other query parameters that need to be added to the URL, in
+    /// order
+    query_parameters: Vec<(String, String)>,
+    /// whether the `ik-sdk-version` query parameter is added to the URL
+    sdk_version: bool,
+    /// file appended to the path of a video, i.e., thumbnail or streaming
+    /// manifest
+    video_asset: Option<&'static str>,
 }
 
 impl Options {
     /// Creates a new instance of `Options`
     pub fn new(transformation: Transformation) -> Self {
         Self {
-            transformation,
+            transformations: vec![transformation],
             ..Default::default()
         }
     }
 
+    /// Appends a transformation step to the chain, applied after the
+    /// previous steps
+    pub fn chain(mut self, val: Transformation) -> Self {
+        self.transformations.push(val);
+        self
+    }
+
+    /// Replaces the chain of transformation steps
+    pub fn transformations(mut self, val: Vec<Transformation>) -> Self {
+        self.transformations = val;
+        self
+    }
+
     /// Sets the endpoint for the image
     pub fn url_endpoint<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
         self.url_endpoint = Some(val.into());
@@ -109,9 +104,30 @@ impl Options {
         self
     }
 
-    /// Sets the src
-    pub fn query_parameters(mut self, val: HashMap<String, String>) -> Self {
-        self.query_parameters = Some(val);
+    /// Sets the query parameters added to the URL, in the order they are
+    /// iterated. The `tr` key is reserved for transformations.
+    pub fn query_parameters<I, K, V>(mut self, val: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.query_parameters = val
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        self
+    }
+
+    /// Appends a query parameter to the URL
+    pub fn query_parameter<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.query_parameters.push((key.into(), value.into()));
+        self
+    }
+
+    /// Adds the `ik-sdk-version` query parameter to the URL
+    pub fn sdk_version(mut self) -> Self {
+        self.sdk_version = true;
         self
     }
 
@@ -120,6 +136,28 @@ impl Options {
         self.transformation_position = val;
         self
     }
+
+    /// Generates the URL of a thumbnail of the video, use
+    /// `Transformation::start_offset` to pick the frame, the first frame is
+    /// used when no transformation is set
+    pub fn thumbnail(mut self) -> Self {
+        self.video_asset = Some(video::THUMBNAIL);
+        self
+    }
+
+    /// Generates the URL of the adaptive streaming manifest of the video
+    /// with the provided resolutions, e.g. `vec![240, 360, 720]`
+    pub fn adaptive_streaming(mut self, protocol: Protocol, resolutions: Vec<u32>) -> Self {
+        let step = self
+            .transformations
+            .pop()
+            .unwrap_or_default()
+            .streaming_resolutions(resolutions);
+
+        self.transformations.push(step);
+        self.video_asset = Some(protocol.manifest());
+        self
+    }
 }
 
 impl Default for Options {
@@ -128,9 +166,11 @@ impl Default for Options {
             url_endpoint: None,
             path: None,
             src: None,
-            transformation: Transformation::new(),
+            transformations: Vec::new(),
             transformation_position: TransformationPosition::default(),
-            query_parameters: None,
+            query_parameters: Vec::new(),
+            sdk_version: false,
+            video_asset: None,
         }
     }
 }
@@ -142,72 +182,184 @@ pub trait Url {
 
 impl Url for ImageKit {
     fn url(&self, opts: Options) -> Result<String> {
-        if opts.path.is_some() && opts.src.is_some() {
-            return Err(anyhow::anyhow!("Either path or src is required"));
+        generate_url(&self.url_endpoint, opts)
+    }
+}
+
+/// Generates URLs without the API keys or an HTTP client, e.g. in the
+/// browser when compiled to WebAssembly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UrlGenerator {
+    url_endpoint: String,
+}
+
+impl UrlGenerator {
+    /// Creates a new instance of `UrlGenerator` for the URL endpoint of the
+    /// account, e.g. `https://ik.imagekit.io/your_imagekit_id`
+    pub fn new<T: ToString>(url_endpoint: T) -> Self {
+        Self {
+            url_endpoint: url_endpoint.to_string(),
         }
+    }
 
-        let transformed = opts.transformation.transform()?;
-        let transformation_position = if opts.src.is_some() {
-            //  If src parameter is being used, then always force the addition of transformation paramters in query
-            &TransformationPosition::Query
-        } else {
-            &opts.transformation_position
-        };
-
-        let query_parameters_str = Utils::concat_query_parameters(&opts);
-        let url_endpoint = opts
-            .url_endpoint
-            .clone()
-            .unwrap_or(self.url_endpoint.clone());
-
-        let generated_url = match transformation_position {
-            TransformationPosition::Path => {
-                let Some(path) = &opts.path else {
-                    return Err(anyhow::anyhow!(
-                        "path should be set for transformation position path"
-                    ));
-                };
-                let path = path.trim_matches('/');
-                let mut generated_url = format!("{url_endpoint}/tr:{transformed}/{path}");
-                if !query_parameters_str.is_empty() {
-                    generated_url.push_str(&format!("?{query_parameters_str}"));
-                }
-                generated_url
-            }
-            TransformationPosition::Query => {
-                let mut generated_url = if let Some(src) = opts.src {
-                    format!("{src}?tr={transformed}")
-                } else {
-                    let path = opts.path.expect("path is expected when src is not given");
-                    format!("{url_endpoint}/{path}?tr={transformed}")
-                };
-                if !query_parameters_str.is_empty() {
-                    generated_url.push_str(&format!("&{query_parameters_str}"));
-                }
-                generated_url
-            }
-        };
-        Ok(generated_url)
+    /// Parses a URL generated by ImageKit back into the `Options` used to
+    /// generate it and its chain of transformations
+    pub fn parse_url<T: AsRef<str>>(&self, url: T) -> Result<(Options, Vec<Transformation>)> {
+        parse::parse_url(&self.url_endpoint, url.as_ref())
     }
 }
 
-pub struct Utils;
+impl Url for UrlGenerator {
+    fn url(&self, opts: Options) -> Result<String> {
+        generate_url(&self.url_endpoint, opts)
+    }
+}
 
-impl Utils {
-    fn concat_query_parameters(opts: &Options) -> String {
-        let Some(query_parameters) = opts.query_parameters.as_ref() else {
-            return String::default();
-        };
+/// Generates the URL of `opts`, using `url_endpoint` unless `opts` sets its
+/// own
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "imagekit.url",
+        level = "debug",
+        skip_all,
+        fields(path = opts.path.as_deref(), src = opts.src.as_deref()),
+        err
+    )
+)]
+pub(crate) fn generate_url(url_endpoint: &str, opts: Options) -> Result<String> {
+    if opts.path.is_some() && opts.src.is_some() {
+        bail!("Either path or src is required");
+    }
 
-        let mut query = String::default();
+    if opts.query_parameters.iter().any(|(key, _)| key == "tr") {
+        bail!("The tr query parameter is reserved for transformations, use Options::chain instead");
+    }
 
-        for (param, value) in query_parameters {
-            let parameter_str = format!("{param}={value}");
-            query.push_str(&format!("&{parameter_str}"));
+    // Video assets, e.g. a thumbnail of the first frame, are also served
+    // without transformations
+    let transformed = match (opts.transformations.is_empty(), opts.video_asset) {
+        (true, Some(_)) => None,
+        _ => Some(Transformation::chain(&opts.transformations)?),
+    };
+    let transformation_position = if opts.src.is_some() {
+        //  If src parameter is being used, then always force the addition of transformation paramters in query
+        TransformationPosition::Query
+    } else {
+        opts.transformation_position
+    };
+
+    let mut url = match (&opts.src, &opts.path) {
+        (Some(src), _) => ParsedUrl::parse(src)?,
+        (None, Some(path)) => {
+            let url_endpoint = opts.url_endpoint.as_deref().unwrap_or(url_endpoint);
+            let mut url = ParsedUrl::parse(url_endpoint)?;
+            let endpoint_path = url.path().trim_end_matches('/').to_string();
+            let path = Utils::encode_path(path);
+
+            match (transformation_position, &transformed) {
+                (TransformationPosition::Path, Some(transformed)) => {
+                    url.set_path(&format!("{endpoint_path}/tr:{transformed}/{path}"))
+                }
+                _ => url.set_path(&format!("{endpoint_path}/{path}")),
+            }
+
+            url
         }
+        (None, None) => bail!("Either path or src is required"),
+    };
+
+    if let Some(asset) = opts.video_asset {
+        let path = format!("{}/{asset}", url.path().trim_end_matches('/'));
+        url.set_path(&path);
+    }
+
+    let mut query: Vec<String> = url
+        .query()
+        .filter(|query| !query.is_empty())
+        .map(String::from)
+        .into_iter()
+        .collect();
+
+    if let (TransformationPosition::Query, Some(transformed)) =
+        (transformation_position, &transformed)
+    {
+        query.push(format!(
+            "tr={}",
+            utf8_percent_encode(transformed, TRANSFORMATION)
+        ));
+    }
+
+    query.extend(Utils::encode_query_parameters(&opts));
+
+    if query.is_empty() {
+        url.set_query(None);
+    } else {
+        url.set_query(Some(&query.join("&")));
+    }
+
+    Ok(url.into())
+}
+
+/// Characters escaped in a path segment, `%` is escaped as paths are
+/// expected to be decoded
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Characters escaped in the `tr` query parameter, `,` and `:` are kept so
+/// the transformation string stays readable and `%` is kept as Base64
+/// values are already escaped
+const TRANSFORMATION: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'&')
+    .add(b'+')
+    .add(b'<')
+    .add(b'=')
+    .add(b'>')
+    .add(b'`');
+
+pub struct Utils;
 
-        query = query.trim_matches('&').into();
+impl Utils {
+    fn encode_query_parameters(opts: &Options) -> Vec<String> {
+        let sdk_version = opts
+            .sdk_version
+            .then(|| (SDK_VERSION_PARAMETER.to_string(), sdk_version()));
+
+        opts.query_parameters
+            .iter()
+            .chain(sdk_version.iter())
+            .map(|(param, value)| {
+                format!(
+                    "{}={}",
+                    utf8_percent_encode(param, COMPONENT),
+                    utf8_percent_encode(value, COMPONENT)
+                )
+            })
+            .collect()
+    }
 
-        query
+    fn encode_path(path: &str) -> String {
+        path.trim_matches('/')
+            .split('/')
+            .map(|segment| utf8_percent_encode(segment, PATH_SEGMENT).to_string())
+            .collect::<Vec<String>>()
+            .join("/")
     }
 }
+
+/// Value of the `ik-sdk-version` query parameter, e.g. `rust-0.1.0`
+pub(crate) fn sdk_version() -> String {
+    format!("rust-{}", env!("CARGO_PKG_VERSION"))
+}
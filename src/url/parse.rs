@@ -0,0 +1,115 @@
+use anyhow::Result;
+use percent_encoding::percent_decode_str;
+use url::Url as ParsedUrl;
+
+use crate::ImageKit;
+
+use super::video::{Protocol, THUMBNAIL};
+use super::{Options, Transformation, TransformationPosition, SDK_VERSION_PARAMETER};
+
+impl ImageKit {
+    /// Parses a URL generated by ImageKit back into the `Options` used to
+    /// generate it and its chain of transformations.
+    ///
+    /// Both the `tr:` path and the `tr` query parameter forms are supported.
+    /// The returned `Options` do not include the transformations, so they
+    /// can be modified before generating the URL again:
+    ///
+    /// ```ignore
+    /// let (opts, mut transformations) = imagekit.parse_url(stored_url)?;
+    /// transformations[0] = transformations[0].clone().width(400);
+    /// let url = imagekit.url(opts.transformations(transformations))?;
+    /// ```
+    pub fn parse_url<T: AsRef<str>>(&self, url: T) -> Result<(Options, Vec<Transformation>)> {
+        parse_url(&self.url_endpoint, url.as_ref())
+    }
+}
+
+/// Parses `url` relative to `url_endpoint`, the transformations are read
+/// from the `tr:` path segment or the `tr` query parameter
+pub(crate) fn parse_url(url_endpoint: &str, url: &str) -> Result<(Options, Vec<Transformation>)> {
+    let parsed = ParsedUrl::parse(url)?;
+    let mut opts = Options::default();
+    let mut transformations: Vec<Transformation> = Vec::new();
+
+    for (key, value) in parsed.query_pairs() {
+        match key.as_ref() {
+            "tr" => transformations = Transformation::parse(value)?,
+            SDK_VERSION_PARAMETER => opts.sdk_version = true,
+            _ => opts
+                .query_parameters
+                .push((key.into_owned(), value.into_owned())),
+        }
+    }
+
+    let origin = parsed.origin().ascii_serialization();
+    let mut segments: Vec<&str> = parsed
+        .path()
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    opts.video_asset = match segments.last() {
+        Some(&THUMBNAIL) => Some(THUMBNAIL),
+        Some(&asset) if asset == Protocol::Hls.manifest() => Some(Protocol::Hls.manifest()),
+        Some(&asset) if asset == Protocol::Dash.manifest() => Some(Protocol::Dash.manifest()),
+        _ => None,
+    };
+
+    if opts.video_asset.is_some() {
+        segments.pop();
+    }
+
+    let url_endpoint = url_endpoint.trim_end_matches('/');
+
+    match segments
+        .iter()
+        .position(|segment| segment.starts_with("tr:"))
+    {
+        Some(index) => {
+            let endpoint = Some(origin)
+                .into_iter()
+                .chain(segments[..index].iter().map(|segment| segment.to_string()))
+                .collect::<Vec<String>>()
+                .join("/");
+
+            let transformation = percent_decode_str(&segments[index][3..]).decode_utf8()?;
+
+            transformations = Transformation::parse(transformation)?;
+            opts.path = Some(decode_path(&segments[index + 1..])?);
+            opts.transformation_position = TransformationPosition::Path;
+
+            if endpoint != url_endpoint {
+                opts.url_endpoint = Some(endpoint);
+            }
+        }
+        None => {
+            let base = format!("{origin}/{}", segments.join("/"));
+
+            opts.transformation_position = TransformationPosition::Query;
+
+            match base
+                .strip_prefix(url_endpoint)
+                .and_then(|path| path.strip_prefix('/'))
+            {
+                Some(path) => {
+                    let segments: Vec<&str> = path.split('/').collect();
+                    opts.path = Some(decode_path(&segments)?);
+                }
+                None => opts.src = Some(base),
+            }
+        }
+    }
+
+    Ok((opts, transformations))
+}
+
+fn decode_path(segments: &[&str]) -> Result<String> {
+    let segments = segments
+        .iter()
+        .map(|segment| Ok(percent_decode_str(segment).decode_utf8()?.into_owned()))
+        .collect::<Result<Vec<String>>>()?;
+
+    Ok(segments.join("/"))
+}
@@ -0,0 +1,228 @@
+use anyhow::{bail, Result};
+
+use super::types::{Dpr, Format};
+use super::{Options, Transformation, Url};
+
+/// Candidates of a `srcset` attribute.
+///
+/// Refer: https://developer.mozilla.org/en-US/docs/Web/HTML/Responsive_images
+#[derive(Clone, Debug, PartialEq)]
+pub enum Breakpoints {
+    /// One candidate per width, described as `<width>w`. Should be used
+    /// along with `sizes`.
+    Widths(Vec<u32>),
+    /// One candidate per device pixel ratio, described as `<dpr>x`. Should
+    /// be used for images with a fixed width.
+    Dpr(Vec<f32>),
+}
+
+impl Breakpoints {
+    /// Commonly used widths, from small phones to large desktop displays
+    pub fn common() -> Self {
+        Breakpoints::Widths(vec![320, 640, 768, 1024, 1366, 1600, 1920])
+    }
+
+    /// Applies the largest width or the smallest DPR to `opts`, used as the
+    /// `src` of browsers not supporting `srcset`
+    fn fallback(&self, opts: &Options) -> Options {
+        match self {
+            Breakpoints::Widths(widths) => {
+                let width = widths.iter().copied().max().unwrap_or_default();
+                with_last_step(opts, |t| t.width(width))
+            }
+            Breakpoints::Dpr(dprs) => {
+                let dpr = dprs.iter().copied().fold(f32::MAX, f32::min);
+                with_last_step(opts, |t| t.dpr(Dpr::Value(dpr)))
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let is_empty = match self {
+            Breakpoints::Widths(widths) => widths.is_empty(),
+            Breakpoints::Dpr(dprs) => dprs.is_empty(),
+        };
+
+        if is_empty {
+            bail!("At least one breakpoint is required to generate a srcset");
+        }
+
+        Ok(())
+    }
+}
+
+/// Attributes of the `<img>` and `<picture>` elements generated by
+/// `Responsive::img` and `Responsive::picture`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Picture {
+    breakpoints: Breakpoints,
+    alt: String,
+    sizes: Option<String>,
+    formats: Vec<Format>,
+    lazy: bool,
+}
+
+impl Picture {
+    /// Creates a new instance of `Picture` with AVIF and WebP sources and
+    /// lazy loading
+    pub fn new(breakpoints: Breakpoints) -> Self {
+        Self {
+            breakpoints,
+            alt: String::default(),
+            sizes: None,
+            formats: vec![Format::Avif, Format::Webp],
+            lazy: true,
+        }
+    }
+
+    /// Sets the alternative text of the image
+    pub fn alt<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.alt = val.into();
+        self
+    }
+
+    /// Sets the `sizes` attribute, e.g. `(max-width: 640px) 100vw, 50vw`
+    pub fn sizes<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.sizes = Some(val.into());
+        self
+    }
+
+    /// Sets the formats of the `<source>` elements, in order of preference
+    pub fn formats(mut self, val: Vec<Format>) -> Self {
+        self.formats = val;
+        self
+    }
+
+    /// Sets whether the image is loaded lazily, `true` by default
+    pub fn lazy(mut self, val: bool) -> Self {
+        self.lazy = val;
+        self
+    }
+}
+
+pub trait Responsive {
+    /// Generates a `srcset` attribute value with one URL per breakpoint.
+    /// The width or DPR of each candidate is applied to the last
+    /// transformation step of `opts`.
+    fn srcset(&self, opts: Options, breakpoints: &Breakpoints) -> Result<String>;
+
+    /// Generates an `<img>` element with `srcset`, `sizes` and `alt`. The
+    /// `src` uses the largest width or the smallest DPR.
+    fn img(&self, opts: Options, picture: &Picture) -> Result<String>;
+
+    /// Generates a `<picture>` element with one `<source>` per format and a
+    /// fallback `<img>` element
+    fn picture(&self, opts: Options, picture: &Picture) -> Result<String>;
+}
+
+impl<T: Url> Responsive for T {
+    fn srcset(&self, opts: Options, breakpoints: &Breakpoints) -> Result<String> {
+        breakpoints.validate()?;
+
+        let candidates = match breakpoints {
+            Breakpoints::Widths(widths) => widths
+                .iter()
+                .map(|&width| {
+                    let url = self.url(with_last_step(&opts, |t| t.width(width)))?;
+                    Ok(format!("{url} {width}w"))
+                })
+                .collect::<Result<Vec<String>>>()?,
+            Breakpoints::Dpr(dprs) => dprs
+                .iter()
+                .map(|&dpr| {
+                    let url = self.url(with_last_step(&opts, |t| t.dpr(Dpr::Value(dpr))))?;
+                    Ok(format!("{url} {dpr}x"))
+                })
+                .collect::<Result<Vec<String>>>()?,
+        };
+
+        Ok(candidates.join(", "))
+    }
+
+    fn img(&self, opts: Options, picture: &Picture) -> Result<String> {
+        picture.breakpoints.validate()?;
+
+        let src = self.url(picture.breakpoints.fallback(&opts))?;
+        let srcset = self.srcset(opts, &picture.breakpoints)?;
+        let mut attributes = vec![attribute("src", &src), attribute("srcset", &srcset)];
+
+        if let Some(sizes) = &picture.sizes {
+            attributes.push(attribute("sizes", sizes));
+        }
+
+        attributes.push(attribute("alt", &picture.alt));
+
+        if picture.lazy {
+            attributes.push(attribute("loading", "lazy"));
+        }
+
+        Ok(format!("<img {}>", attributes.join(" ")))
+    }
+
+    fn picture(&self, opts: Options, picture: &Picture) -> Result<String> {
+        let mut html = String::from("<picture>");
+
+        for format in &picture.formats {
+            let Some(mime) = mime_type(format) else {
+                bail!("Format \"{format}\" cannot be used as a picture source");
+            };
+            let srcset = self.srcset(
+                with_last_step(&opts, |t| t.format(*format)),
+                &picture.breakpoints,
+            )?;
+            let mut attributes = vec![attribute("type", mime), attribute("srcset", &srcset)];
+
+            if let Some(sizes) = &picture.sizes {
+                attributes.push(attribute("sizes", sizes));
+            }
+
+            html.push_str(&format!("<source {}>", attributes.join(" ")));
+        }
+
+        html.push_str(&self.img(opts, picture)?);
+        html.push_str("</picture>");
+
+        Ok(html)
+    }
+}
+
+/// Clones `opts` applying `f` to its last transformation step, a step is
+/// added when there are none
+fn with_last_step<F>(opts: &Options, f: F) -> Options
+where
+    F: FnOnce(Transformation) -> Transformation,
+{
+    let mut opts = opts.clone();
+    let step = opts.transformations.pop().unwrap_or_default();
+
+    opts.transformations.push(f(step));
+    opts
+}
+
+fn mime_type(format: &Format) -> Option<&'static str> {
+    match format {
+        Format::Avif => Some("image/avif"),
+        Format::Webp => Some("image/webp"),
+        Format::Jpg => Some("image/jpeg"),
+        Format::Png => Some("image/png"),
+        Format::Gif => Some("image/gif"),
+        Format::Svg => Some("image/svg+xml"),
+        _ => None,
+    }
+}
+
+fn attribute(name: &str, value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            c => escaped.push(c),
+        }
+    }
+
+    format!("{name}=\"{escaped}\"")
+}
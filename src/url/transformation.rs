@@ -0,0 +1,852 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+
+use super::ai::AiEffect;
+use super::layer::Layer;
+use super::types::{
+    validate_color, Border, Crop, CropMode, Dimension, Dpr, Flip, Focus, Format, Gradient, Radius,
+    Rotation, Shadow, Sharpen, Trim, UnsharpMask,
+};
+use super::video::{AudioCodec, VideoCodec};
+
+/// A single transformation step applied to an image.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transformation {
+    named: Option<String>,
+    width: Option<Dimension>,
+    height: Option<Dimension>,
+    aspect_ratio: Option<String>,
+    crop: Option<Crop>,
+    crop_mode: Option<CropMode>,
+    focus: Option<Focus>,
+    x: Option<u32>,
+    y: Option<u32>,
+    x_center: Option<u32>,
+    y_center: Option<u32>,
+    zoom: Option<f32>,
+    dpr: Option<Dpr>,
+    format: Option<Format>,
+    quality: Option<u8>,
+    lossless: Option<bool>,
+    progressive: Option<bool>,
+    metadata: Option<bool>,
+    color_profile: Option<bool>,
+    blur: Option<u32>,
+    grayscale: bool,
+    sharpen: Option<Sharpen>,
+    unsharp_mask: Option<UnsharpMask>,
+    contrast: bool,
+    shadow: Option<Shadow>,
+    gradient: Option<Gradient>,
+    background: Option<String>,
+    border: Option<Border>,
+    radius: Option<Radius>,
+    rotation: Option<Rotation>,
+    flip: Option<Flip>,
+    trim: Option<Trim>,
+    ai: Vec<AiEffect>,
+    video_codec: Option<VideoCodec>,
+    audio_codec: Option<AudioCodec>,
+    start_offset: Option<f32>,
+    end_offset: Option<f32>,
+    duration: Option<f32>,
+    bitrate: Option<u32>,
+    streaming_resolutions: Vec<u32>,
+    raw: Vec<(String, String)>,
+    layers: Vec<Layer>,
+}
+
+impl Transformation {
+    pub fn new() -> Self {
+        Self { ..Self::default() }
+    }
+
+    /// Creates a transformation from a named transformation (`n-`) defined
+    /// in the ImageKit dashboard, other parameters can still be set and are
+    /// applied along with it
+    ///
+    /// Refer: https://docs.imagekit.io/features/named-transformations
+    pub fn named<T: AsRef<str> + Into<String>>(name: T) -> Self {
+        Self {
+            named: Some(name.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a parameter not yet modeled by `Transformation` as `key-value`,
+    /// or only `key` when `value` is empty. Raw parameters are appended
+    /// after the typed ones in the same order they are added
+    pub fn raw<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: AsRef<str> + Into<String>,
+        V: AsRef<str> + Into<String>,
+    {
+        self.raw.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets the output width in pixels (`w-`)
+    pub fn width(mut self, val: u32) -> Self {
+        self.width = Some(Dimension::Pixels(val));
+        self
+    }
+
+    /// Sets the output width relative to the original one (`w-0.5`), must be
+    /// greater than `0` and less than `1`
+    pub fn relative_width(mut self, val: f32) -> Self {
+        self.width = Some(Dimension::Relative(val));
+        self
+    }
+
+    /// Sets the output height in pixels (`h-`)
+    pub fn height(mut self, val: u32) -> Self {
+        self.height = Some(Dimension::Pixels(val));
+        self
+    }
+
+    /// Sets the output height relative to the original one (`h-0.5`), must
+    /// be greater than `0` and less than `1`
+    pub fn relative_height(mut self, val: f32) -> Self {
+        self.height = Some(Dimension::Relative(val));
+        self
+    }
+
+    /// Sets the output aspect ratio (`ar-`), e.g. `aspect_ratio(4, 3)`
+    pub fn aspect_ratio(mut self, width: u32, height: u32) -> Self {
+        self.aspect_ratio = Some(format!("{width}-{height}"));
+        self
+    }
+
+    #[deprecated(note = "use `Transformation::aspect_ratio` instead")]
+    pub fn aspect_raio<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.aspect_ratio = Some(val.into());
+        self
+    }
+
+    /// Sets the crop strategy (`c-`)
+    pub fn crop(mut self, val: Crop) -> Self {
+        self.crop = Some(val);
+        self
+    }
+
+    /// Sets the crop mode (`cm-`)
+    pub fn crop_mode(mut self, val: CropMode) -> Self {
+        self.crop_mode = Some(val);
+        self
+    }
+
+    /// Sets the focus area (`fo-`)
+    pub fn focus(mut self, val: Focus) -> Self {
+        self.focus = Some(val);
+        self
+    }
+
+    /// Sets the top-left `x` coordinate used with `CropMode::Extract`
+    pub fn x(mut self, val: u32) -> Self {
+        self.x = Some(val);
+        self
+    }
+
+    /// Sets the top-left `y` coordinate used with `CropMode::Extract`
+    pub fn y(mut self, val: u32) -> Self {
+        self.y = Some(val);
+        self
+    }
+
+    /// Sets the center `x` coordinate (`xc-`) used with `CropMode::Extract`
+    pub fn x_center(mut self, val: u32) -> Self {
+        self.x_center = Some(val);
+        self
+    }
+
+    /// Sets the center `y` coordinate (`yc-`) used with `CropMode::Extract`
+    pub fn y_center(mut self, val: u32) -> Self {
+        self.y_center = Some(val);
+        self
+    }
+
+    /// Sets the zoom level (`z-`) used along with `Focus::Face`
+    pub fn zoom(mut self, val: f32) -> Self {
+        self.zoom = Some(val);
+        self
+    }
+
+    /// Sets the Device Pixel Ratio (`dpr-`)
+    pub fn dpr(mut self, val: Dpr) -> Self {
+        self.dpr = Some(val);
+        self
+    }
+
+    /// Sets the output format (`f-`)
+    pub fn format(mut self, val: Format) -> Self {
+        self.format = Some(val);
+        self
+    }
+
+    /// Sets the output quality (`q-`), between `1` and `100`
+    pub fn quality(mut self, val: u8) -> Self {
+        self.quality = Some(val);
+        self
+    }
+
+    /// Enables or disables lossless compression (`lo-`) for WebP and PNG
+    pub fn lossless(mut self, val: bool) -> Self {
+        self.lossless = Some(val);
+        self
+    }
+
+    /// Enables or disables progressive rendering (`pr-`) for JPEG and PNG
+    pub fn progressive(mut self, val: bool) -> Self {
+        self.progressive = Some(val);
+        self
+    }
+
+    /// Keeps or strips the image metadata (`md-`)
+    pub fn metadata(mut self, val: bool) -> Self {
+        self.metadata = Some(val);
+        self
+    }
+
+    /// Keeps or strips the image color profile (`cp-`)
+    pub fn color_profile(mut self, val: bool) -> Self {
+        self.color_profile = Some(val);
+        self
+    }
+
+    /// Applies a gaussian blur (`bl-`), between `1` and `100`
+    pub fn blur(mut self, val: u32) -> Self {
+        self.blur = Some(val);
+        self
+    }
+
+    /// Turns the image into grayscale (`e-grayscale`)
+    pub fn grayscale(mut self) -> Self {
+        self.grayscale = true;
+        self
+    }
+
+    /// Sharpens the image (`e-sharpen`)
+    pub fn sharpen(mut self, val: Sharpen) -> Self {
+        self.sharpen = Some(val);
+        self
+    }
+
+    /// Applies an unsharp mask (`e-usm`)
+    pub fn unsharp_mask(mut self, val: UnsharpMask) -> Self {
+        self.unsharp_mask = Some(val);
+        self
+    }
+
+    /// Stretches the contrast of the image (`e-contrast`)
+    pub fn contrast(mut self) -> Self {
+        self.contrast = true;
+        self
+    }
+
+    /// Adds a shadow under the image (`e-shadow`)
+    pub fn shadow(mut self, val: Shadow) -> Self {
+        self.shadow = Some(val);
+        self
+    }
+
+    /// Overlays a gradient on the image (`e-gradient`)
+    pub fn gradient(mut self, val: Gradient) -> Self {
+        self.gradient = Some(val);
+        self
+    }
+
+    /// Sets the background color (`bg-`) used when padding, either as an
+    /// hexadecimal code (`FF0000`) or a color name (`red`)
+    pub fn background<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.background = Some(val.into());
+        self
+    }
+
+    /// Adds a border around the image (`b-`)
+    pub fn border(mut self, val: Border) -> Self {
+        self.border = Some(val);
+        self
+    }
+
+    /// Rounds the corners of the image (`r-`)
+    pub fn radius(mut self, val: Radius) -> Self {
+        self.radius = Some(val);
+        self
+    }
+
+    /// Rotates the image (`rt-`)
+    pub fn rotate(mut self, val: Rotation) -> Self {
+        self.rotation = Some(val);
+        self
+    }
+
+    /// Flips the image (`fl-`)
+    pub fn flip(mut self, val: Flip) -> Self {
+        self.flip = Some(val);
+        self
+    }
+
+    /// Trims solid borders around the image (`t-`)
+    pub fn trim(mut self, val: Trim) -> Self {
+        self.trim = Some(val);
+        self
+    }
+
+    /// Applies an AI transformation, effects are applied in the same order
+    /// they are added
+    pub fn ai(mut self, val: AiEffect) -> Self {
+        self.ai.push(val);
+        self
+    }
+
+    /// Sets the video codec (`vc-`)
+    pub fn video_codec(mut self, val: VideoCodec) -> Self {
+        self.video_codec = Some(val);
+        self
+    }
+
+    /// Sets the audio codec (`ac-`)
+    pub fn audio_codec(mut self, val: AudioCodec) -> Self {
+        self.audio_codec = Some(val);
+        self
+    }
+
+    /// Sets the start of the video in seconds (`so-`), also used to pick
+    /// the frame of a thumbnail
+    pub fn start_offset(mut self, val: f32) -> Self {
+        self.start_offset = Some(val);
+        self
+    }
+
+    /// Sets the end of the video in seconds (`eo-`)
+    pub fn end_offset(mut self, val: f32) -> Self {
+        self.end_offset = Some(val);
+        self
+    }
+
+    /// Sets the duration of the video in seconds (`du-`)
+    pub fn duration(mut self, val: f32) -> Self {
+        self.duration = Some(val);
+        self
+    }
+
+    /// Sets the video bitrate in kilobits per second (`br-`)
+    pub fn bitrate(mut self, val: u32) -> Self {
+        self.bitrate = Some(val);
+        self
+    }
+
+    /// Sets the resolutions included in an adaptive streaming manifest
+    /// (`sr-`), prefer `url::Options::adaptive_streaming` which also sets the
+    /// manifest file
+    pub fn streaming_resolutions(mut self, val: Vec<u32>) -> Self {
+        self.streaming_resolutions = val;
+        self
+    }
+
+    /// Places a layer on top of the image, layers are drawn in the same
+    /// order they are added
+    pub fn layer<T: Into<Layer>>(mut self, val: T) -> Self {
+        self.layers.push(val.into());
+        self
+    }
+
+    pub fn transform(&self) -> Result<String> {
+        let params = self.params()?;
+
+        if params.is_empty() {
+            bail!("No transformation applied");
+        }
+
+        Ok(params.join(","))
+    }
+
+    /// Serializes a chain of transformations, where each step is applied to
+    /// the result of the previous one.
+    ///
+    /// Refer: https://docs.imagekit.io/features/image-transformations/chained-transformations
+    pub fn chain(steps: &[Transformation]) -> Result<String> {
+        if steps.is_empty() {
+            bail!("No transformation applied");
+        }
+
+        let steps = steps
+            .iter()
+            .map(Transformation::transform)
+            .collect::<Result<Vec<String>>>()?;
+
+        Ok(steps.join(":"))
+    }
+
+    /// Parses a chain of transformations such as `w-200,h-200:rt-90`, as
+    /// found after `tr:` in the path or in the `tr` query parameter
+    pub fn parse<T: AsRef<str>>(val: T) -> Result<Vec<Transformation>> {
+        val.as_ref().split(':').map(str::parse).collect()
+    }
+
+    /// Builds a transformation from its comma separated parameters.
+    ///
+    /// Parameters which are not modeled by `Transformation`, or whose value
+    /// is not supported by the typed setters, are kept as raw parameters.
+    pub(crate) fn from_tokens(tokens: &[&str]) -> Result<Self> {
+        let mut transformation = Transformation::new();
+        let mut index = 0;
+
+        while index < tokens.len() {
+            let token = tokens[index];
+
+            if token == "l-end" {
+                bail!("Found \"l-end\" without an open layer");
+            }
+
+            if let Some(kind) = token.strip_prefix("l-") {
+                let end = Transformation::layer_end(tokens, index)?;
+                let layer = Layer::parse(kind, &tokens[index + 1..end])?;
+
+                transformation.layers.push(layer);
+                index = end + 1;
+                continue;
+            }
+
+            let (key, value) = token.split_once('-').unwrap_or((token, ""));
+
+            if transformation.set(key, value).is_err() {
+                transformation
+                    .raw
+                    .push((key.to_string(), value.to_string()));
+            }
+
+            index += 1;
+        }
+
+        transformation.validate()?;
+
+        Ok(transformation)
+    }
+
+    /// Finds the `l-end` closing the layer opened at `start`
+    fn layer_end(tokens: &[&str], start: usize) -> Result<usize> {
+        let mut depth = 0;
+
+        for (index, token) in tokens.iter().enumerate().skip(start + 1) {
+            if *token == "l-end" {
+                if depth == 0 {
+                    return Ok(index);
+                }
+
+                depth -= 1;
+            } else if token.starts_with("l-") {
+                depth += 1;
+            }
+        }
+
+        bail!("Layer \"{}\" is missing its \"l-end\"", tokens[start]);
+    }
+
+    /// Applies a single parameter, values are parsed before updating the
+    /// transformation so it is left untouched on error
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "n" => self.named = Some(value.to_string()),
+            "w" => self.width = Some(value.parse()?),
+            "h" => self.height = Some(value.parse()?),
+            "ar" => self.aspect_ratio = Some(value.to_string()),
+            "c" => self.crop = Some(value.parse()?),
+            "cm" => self.crop_mode = Some(value.parse()?),
+            "fo" => self.focus = Some(value.parse()?),
+            "x" => self.x = Some(value.parse()?),
+            "y" => self.y = Some(value.parse()?),
+            "xc" => self.x_center = Some(value.parse()?),
+            "yc" => self.y_center = Some(value.parse()?),
+            "z" => self.zoom = Some(value.parse()?),
+            "dpr" => self.dpr = Some(value.parse()?),
+            "f" => self.format = Some(value.parse()?),
+            "q" => self.quality = Some(value.parse()?),
+            "lo" => self.lossless = Some(value.parse()?),
+            "pr" => self.progressive = Some(value.parse()?),
+            "md" => self.metadata = Some(value.parse()?),
+            "cp" => self.color_profile = Some(value.parse()?),
+            "bl" => self.blur = Some(value.parse()?),
+            "e" => match value {
+                "grayscale" => self.grayscale = true,
+                "contrast" => self.contrast = true,
+                _ if value.starts_with("sharpen") => self.sharpen = Some(value.parse()?),
+                _ if value.starts_with("usm-") => self.unsharp_mask = Some(value.parse()?),
+                _ if value.starts_with("shadow") => self.shadow = Some(value.parse()?),
+                _ if value.starts_with("gradient") => self.gradient = Some(value.parse()?),
+                _ => self.ai.push(AiEffect::parse(key, value)?),
+            },
+            "bg" if value.starts_with("genfill") => self.ai.push(AiEffect::parse(key, value)?),
+            "bg" => self.background = Some(value.to_string()),
+            "b" => self.border = Some(value.parse()?),
+            "r" => self.radius = Some(value.parse()?),
+            "rt" => self.rotation = Some(value.parse()?),
+            "fl" => self.flip = Some(value.parse()?),
+            "t" => self.trim = Some(value.parse()?),
+            "vc" => self.video_codec = Some(value.parse()?),
+            "ac" => self.audio_codec = Some(value.parse()?),
+            "so" => self.start_offset = Some(value.parse()?),
+            "eo" => self.end_offset = Some(value.parse()?),
+            "du" => self.duration = Some(value.parse()?),
+            "br" => self.bitrate = Some(value.parse()?),
+            "sr" => {
+                self.streaming_resolutions = value
+                    .split('_')
+                    .map(str::parse::<u32>)
+                    .collect::<std::result::Result<Vec<u32>, _>>()?
+            }
+            _ => bail!("Unsupported parameter \"{key}\""),
+        }
+
+        Ok(())
+    }
+
+    /// Validates and serializes each parameter of this step, without
+    /// failing when no parameter is set so it can be used for nested
+    /// transformations.
+    pub(crate) fn params(&self) -> Result<Vec<String>> {
+        self.validate()?;
+
+        let mut params: Vec<String> = Vec::new();
+
+        if let Some(named) = &self.named {
+            params.push(format!("n-{named}"));
+        }
+
+        if let Some(width) = &self.width {
+            params.push(format!("w-{width}"));
+        }
+
+        if let Some(height) = &self.height {
+            params.push(format!("h-{height}"));
+        }
+
+        if let Some(aspect_ratio) = &self.aspect_ratio {
+            params.push(format!("ar-{aspect_ratio}"));
+        }
+
+        if let Some(crop) = &self.crop {
+            params.push(format!("c-{crop}"));
+        }
+
+        if let Some(crop_mode) = &self.crop_mode {
+            params.push(format!("cm-{crop_mode}"));
+        }
+
+        if let Some(focus) = &self.focus {
+            params.push(format!("fo-{focus}"));
+        }
+
+        if let Some(x) = self.x {
+            params.push(format!("x-{x}"));
+        }
+
+        if let Some(y) = self.y {
+            params.push(format!("y-{y}"));
+        }
+
+        if let Some(x_center) = self.x_center {
+            params.push(format!("xc-{x_center}"));
+        }
+
+        if let Some(y_center) = self.y_center {
+            params.push(format!("yc-{y_center}"));
+        }
+
+        if let Some(zoom) = self.zoom {
+            params.push(format!("z-{zoom}"));
+        }
+
+        if let Some(dpr) = &self.dpr {
+            params.push(format!("dpr-{dpr}"));
+        }
+
+        if let Some(format) = &self.format {
+            params.push(format!("f-{format}"));
+        }
+
+        if let Some(quality) = self.quality {
+            params.push(format!("q-{quality}"));
+        }
+
+        if let Some(lossless) = self.lossless {
+            params.push(format!("lo-{lossless}"));
+        }
+
+        if let Some(progressive) = self.progressive {
+            params.push(format!("pr-{progressive}"));
+        }
+
+        if let Some(metadata) = self.metadata {
+            params.push(format!("md-{metadata}"));
+        }
+
+        if let Some(color_profile) = self.color_profile {
+            params.push(format!("cp-{color_profile}"));
+        }
+
+        if let Some(blur) = self.blur {
+            params.push(format!("bl-{blur}"));
+        }
+
+        if self.grayscale {
+            params.push("e-grayscale".into());
+        }
+
+        if let Some(sharpen) = &self.sharpen {
+            params.push(format!("e-{sharpen}"));
+        }
+
+        if let Some(unsharp_mask) = &self.unsharp_mask {
+            params.push(format!("e-{unsharp_mask}"));
+        }
+
+        if self.contrast {
+            params.push("e-contrast".into());
+        }
+
+        if let Some(shadow) = &self.shadow {
+            params.push(format!("e-{shadow}"));
+        }
+
+        if let Some(gradient) = &self.gradient {
+            params.push(format!("e-{gradient}"));
+        }
+
+        if let Some(background) = &self.background {
+            params.push(format!("bg-{background}"));
+        }
+
+        if let Some(border) = &self.border {
+            params.push(format!("b-{border}"));
+        }
+
+        if let Some(radius) = &self.radius {
+            params.push(format!("r-{radius}"));
+        }
+
+        if let Some(rotation) = &self.rotation {
+            params.push(format!("rt-{rotation}"));
+        }
+
+        if let Some(flip) = &self.flip {
+            params.push(format!("fl-{flip}"));
+        }
+
+        if let Some(trim) = &self.trim {
+            params.push(format!("t-{trim}"));
+        }
+
+        for effect in &self.ai {
+            params.push(effect.param()?);
+        }
+
+        if let Some(video_codec) = &self.video_codec {
+            params.push(format!("vc-{video_codec}"));
+        }
+
+        if let Some(audio_codec) = &self.audio_codec {
+            params.push(format!("ac-{audio_codec}"));
+        }
+
+        if let Some(start_offset) = self.start_offset {
+            params.push(format!("so-{start_offset}"));
+        }
+
+        if let Some(end_offset) = self.end_offset {
+            params.push(format!("eo-{end_offset}"));
+        }
+
+        if let Some(duration) = self.duration {
+            params.push(format!("du-{duration}"));
+        }
+
+        if let Some(bitrate) = self.bitrate {
+            params.push(format!("br-{bitrate}"));
+        }
+
+        if !self.streaming_resolutions.is_empty() {
+            let resolutions = self
+                .streaming_resolutions
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<String>>();
+
+            params.push(format!("sr-{}", resolutions.join("_")));
+        }
+
+        for (key, value) in &self.raw {
+            if value.is_empty() {
+                params.push(key.clone());
+            } else {
+                params.push(format!("{key}-{value}"));
+            }
+        }
+
+        for layer in &self.layers {
+            params.extend(layer.params()?);
+        }
+
+        Ok(params)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(named) = &self.named {
+            let is_valid = named
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+
+            if named.is_empty() || !is_valid {
+                bail!("Invalid named transformation \"{named}\"");
+            }
+        }
+
+        for (key, value) in &self.raw {
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                bail!("Invalid raw parameter key \"{key}\"");
+            }
+
+            if value.contains([',', ':', '/', '?', '&', '#']) {
+                bail!("Invalid raw parameter value \"{value}\"");
+            }
+        }
+
+        for dimension in [&self.width, &self.height].into_iter().flatten() {
+            if let Dimension::Relative(val) = dimension {
+                if !(*val > 0.0 && *val < 1.0) {
+                    bail!("Relative dimensions must be between 0 and 1, got {val}");
+                }
+            }
+        }
+
+        if let Some(aspect_ratio) = &self.aspect_ratio {
+            let is_valid = aspect_ratio
+                .split_once('-')
+                .map(|(w, h)| w.parse::<u32>().is_ok() && h.parse::<u32>().is_ok())
+                .unwrap_or(false);
+
+            if !is_valid {
+                bail!("Invalid aspect ratio \"{aspect_ratio}\", expected \"<width>-<height>\"");
+            }
+        }
+
+        if let Some(Focus::Object(object)) = &self.focus {
+            if object.is_empty() || !object.chars().all(|c| c.is_ascii_alphanumeric()) {
+                bail!("Invalid focus object \"{object}\"");
+            }
+        }
+
+        if let Some(zoom) = self.zoom {
+            if !(zoom > 0.0 && zoom.is_finite()) {
+                bail!("Zoom must be a finite number greater than 0, got {zoom}");
+            }
+        }
+
+        if let Some(Dpr::Value(dpr)) = self.dpr {
+            if !(0.1..=5.0).contains(&dpr) {
+                bail!("DPR must be between 0.1 and 5, got {dpr}");
+            }
+        }
+
+        if let Some(quality) = self.quality {
+            if !(1..=100).contains(&quality) {
+                bail!("Quality must be between 1 and 100, got {quality}");
+            }
+        }
+
+        if let Some(blur) = self.blur {
+            if !(1..=100).contains(&blur) {
+                bail!("Blur must be between 1 and 100, got {blur}");
+            }
+        }
+
+        if let Some(UnsharpMask {
+            radius,
+            sigma,
+            amount,
+            threshold,
+        }) = self.unsharp_mask
+        {
+            if [radius, sigma, amount, threshold]
+                .iter()
+                .any(|val| !(*val >= 0.0 && val.is_finite()))
+            {
+                bail!("Unsharp mask values must be finite and not negative");
+            }
+        }
+
+        if let Some(shadow) = &self.shadow {
+            shadow.validate()?;
+        }
+
+        if let Some(gradient) = &self.gradient {
+            gradient.validate()?;
+        }
+
+        if let Some(background) = &self.background {
+            validate_color(background)?;
+        }
+
+        if let Some(border) = &self.border {
+            validate_color(&border.color)?;
+        }
+
+        if let Some(Rotation::Degrees(degrees)) = self.rotation {
+            if !(-360..=360).contains(&degrees) {
+                bail!("Rotation must be between -360 and 360 degrees, got {degrees}");
+            }
+        }
+
+        for offset in [self.start_offset, self.end_offset].into_iter().flatten() {
+            if !(offset >= 0.0 && offset.is_finite()) {
+                bail!("Video offsets must be finite and not negative, got {offset}");
+            }
+        }
+
+        if let (Some(start_offset), Some(end_offset)) = (self.start_offset, self.end_offset) {
+            if end_offset <= start_offset {
+                bail!("Video end offset must be greater than the start offset");
+            }
+        }
+
+        if let Some(duration) = self.duration {
+            if !(duration > 0.0 && duration.is_finite()) {
+                bail!("Video duration must be a finite number greater than 0, got {duration}");
+            }
+        }
+
+        if self.bitrate == Some(0) {
+            bail!("Video bitrate must be greater than 0");
+        }
+
+        if self.streaming_resolutions.contains(&0) {
+            bail!("Streaming resolutions must be greater than 0");
+        }
+
+        if let Some(Trim::Threshold(threshold)) = self.trim {
+            if !(1..=99).contains(&threshold) {
+                bail!("Trim threshold must be between 1 and 99, got {threshold}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Transformation {
+    type Err = Error;
+
+    /// Parses a single transformation step such as `w-200,h-200,c-at_max`
+    fn from_str(s: &str) -> Result<Self> {
+        let tokens: Vec<&str> = s.split(',').filter(|token| !token.is_empty()).collect();
+
+        if tokens.is_empty() {
+            bail!("No transformation applied");
+        }
+
+        Transformation::from_tokens(&tokens)
+    }
+}
@@ -0,0 +1,812 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Width or height of the output image.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#width-w
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dimension {
+    /// Size in pixels
+    Pixels(u32),
+    /// Size relative to the original image, e.g. `0.5` for half of it
+    Relative(f32),
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dimension::Pixels(val) => write!(f, "{val}"),
+            Dimension::Relative(val) => write!(f, "{val}"),
+        }
+    }
+}
+
+impl FromStr for Dimension {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.contains('.') {
+            return Ok(Dimension::Relative(s.parse()?));
+        }
+
+        Ok(Dimension::Pixels(s.parse()?))
+    }
+}
+
+/// Crop strategy used when both width and height are provided.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#crop-crop-modes-and-focus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Crop {
+    /// `c-maintain_ratio`, crops the image keeping its aspect ratio
+    MaintainRatio,
+    /// `c-force`, squeezes the image to the exact dimensions
+    Force,
+    /// `c-at_least`, resizes keeping the aspect ratio so both dimensions are
+    /// equal or larger than the requested ones
+    AtLeast,
+    /// `c-at_max`, resizes keeping the aspect ratio so both dimensions are
+    /// equal or smaller than the requested ones
+    AtMax,
+}
+
+impl Display for Crop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Crop::MaintainRatio => write!(f, "maintain_ratio"),
+            Crop::Force => write!(f, "force"),
+            Crop::AtLeast => write!(f, "at_least"),
+            Crop::AtMax => write!(f, "at_max"),
+        }
+    }
+}
+
+impl FromStr for Crop {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "maintain_ratio" => Ok(Crop::MaintainRatio),
+            "force" => Ok(Crop::Force),
+            "at_least" => Ok(Crop::AtLeast),
+            "at_max" => Ok(Crop::AtMax),
+            _ => bail!("Invalid crop \"{s}\""),
+        }
+    }
+}
+
+/// Crop mode, changes how the image is fitted into the requested dimensions.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#crop-crop-modes-and-focus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CropMode {
+    /// `cm-pad_resize`, resizes and pads the image to fit
+    PadResize,
+    /// `cm-extract`, extracts a region of the original image
+    Extract,
+    /// `cm-pad_extract`, extracts a region and pads it if needed
+    PadExtract,
+}
+
+impl Display for CropMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CropMode::PadResize => write!(f, "pad_resize"),
+            CropMode::Extract => write!(f, "extract"),
+            CropMode::PadExtract => write!(f, "pad_extract"),
+        }
+    }
+}
+
+impl FromStr for CropMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pad_resize" => Ok(CropMode::PadResize),
+            "extract" => Ok(CropMode::Extract),
+            "pad_extract" => Ok(CropMode::PadExtract),
+            _ => bail!("Invalid crop mode \"{s}\""),
+        }
+    }
+}
+
+/// Focus area used when cropping or extracting.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#focus-fo
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Focus {
+    Center,
+    Top,
+    Left,
+    Bottom,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// `fo-auto`, focus on the most relevant area of the image
+    Auto,
+    /// `fo-face`, focus on the faces in the image
+    Face,
+    /// `fo-custom`, focus on the custom coordinates stored for the image
+    Custom,
+    /// Focus on an object detected in the image, e.g. `fo-dog`
+    Object(String),
+}
+
+impl Display for Focus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Focus::Center => write!(f, "center"),
+            Focus::Top => write!(f, "top"),
+            Focus::Left => write!(f, "left"),
+            Focus::Bottom => write!(f, "bottom"),
+            Focus::Right => write!(f, "right"),
+            Focus::TopLeft => write!(f, "top_left"),
+            Focus::TopRight => write!(f, "top_right"),
+            Focus::BottomLeft => write!(f, "bottom_left"),
+            Focus::BottomRight => write!(f, "bottom_right"),
+            Focus::Auto => write!(f, "auto"),
+            Focus::Face => write!(f, "face"),
+            Focus::Custom => write!(f, "custom"),
+            Focus::Object(object) => write!(f, "{object}"),
+        }
+    }
+}
+
+impl FromStr for Focus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "center" => Ok(Focus::Center),
+            "top" => Ok(Focus::Top),
+            "left" => Ok(Focus::Left),
+            "bottom" => Ok(Focus::Bottom),
+            "right" => Ok(Focus::Right),
+            "top_left" => Ok(Focus::TopLeft),
+            "top_right" => Ok(Focus::TopRight),
+            "bottom_left" => Ok(Focus::BottomLeft),
+            "bottom_right" => Ok(Focus::BottomRight),
+            "auto" => Ok(Focus::Auto),
+            "face" => Ok(Focus::Face),
+            "custom" => Ok(Focus::Custom),
+            object if !object.is_empty() && object.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                Ok(Focus::Object(object.to_string()))
+            }
+            _ => bail!("Invalid focus \"{s}\""),
+        }
+    }
+}
+
+/// Device Pixel Ratio used to scale the output dimensions.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#dpr-dpr
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dpr {
+    /// `dpr-auto`, uses the DPR reported by the browser
+    Auto,
+    /// A DPR between `0.1` and `5`
+    Value(f32),
+}
+
+impl Display for Dpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dpr::Auto => write!(f, "auto"),
+            Dpr::Value(val) => write!(f, "{val}"),
+        }
+    }
+}
+
+impl FromStr for Dpr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(Dpr::Auto),
+            val => Ok(Dpr::Value(val.parse()?)),
+        }
+    }
+}
+
+/// Output format of the image (`f-`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#format-f
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// `f-auto`, picks the best format supported by the browser
+    Auto,
+    Webp,
+    Avif,
+    Jpg,
+    Png,
+    Gif,
+    Svg,
+    /// `f-orig`, keeps the original format
+    Orig,
+    /// Video only
+    Mp4,
+    /// Video only
+    Webm,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Auto => write!(f, "auto"),
+            Format::Webp => write!(f, "webp"),
+            Format::Avif => write!(f, "avif"),
+            Format::Jpg => write!(f, "jpg"),
+            Format::Png => write!(f, "png"),
+            Format::Gif => write!(f, "gif"),
+            Format::Svg => write!(f, "svg"),
+            Format::Orig => write!(f, "orig"),
+            Format::Mp4 => write!(f, "mp4"),
+            Format::Webm => write!(f, "webm"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(Format::Auto),
+            "webp" => Ok(Format::Webp),
+            "avif" => Ok(Format::Avif),
+            "jpg" => Ok(Format::Jpg),
+            "png" => Ok(Format::Png),
+            "gif" => Ok(Format::Gif),
+            "svg" => Ok(Format::Svg),
+            "orig" => Ok(Format::Orig),
+            "mp4" => Ok(Format::Mp4),
+            "webm" => Ok(Format::Webm),
+            _ => bail!("Invalid format \"{s}\""),
+        }
+    }
+}
+
+/// Sharpening applied to the image (`e-sharpen`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/image-enhancement-and-color-manipulation#sharpen-e-sharpen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sharpen {
+    /// `e-sharpen`, uses the default sharpening amount
+    Default,
+    /// `e-sharpen-<amount>`
+    Amount(u32),
+}
+
+impl Display for Sharpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sharpen::Default => write!(f, "sharpen"),
+            Sharpen::Amount(amount) => write!(f, "sharpen-{amount}"),
+        }
+    }
+}
+
+impl FromStr for Sharpen {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.strip_prefix("sharpen") {
+            Some("") => Ok(Sharpen::Default),
+            Some(amount) => match amount.strip_prefix('-') {
+                Some(amount) => Ok(Sharpen::Amount(amount.parse()?)),
+                None => bail!("Invalid sharpen \"{s}\""),
+            },
+            None => bail!("Invalid sharpen \"{s}\""),
+        }
+    }
+}
+
+/// Unsharp masking applied to the image (`e-usm`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/image-enhancement-and-color-manipulation#unsharp-mask-e-usm
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnsharpMask {
+    pub radius: f32,
+    pub sigma: f32,
+    pub amount: f32,
+    pub threshold: f32,
+}
+
+impl UnsharpMask {
+    pub fn new(radius: f32, sigma: f32, amount: f32, threshold: f32) -> Self {
+        Self {
+            radius,
+            sigma,
+            amount,
+            threshold,
+        }
+    }
+}
+
+impl Display for UnsharpMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "usm-{}-{}-{}-{}",
+            self.radius, self.sigma, self.amount, self.threshold
+        )
+    }
+}
+
+impl FromStr for UnsharpMask {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some(values) = s.strip_prefix("usm-") else {
+            bail!("Invalid unsharp mask \"{s}\"");
+        };
+        let values = values
+            .split('-')
+            .map(str::parse::<f32>)
+            .collect::<std::result::Result<Vec<f32>, _>>()?;
+
+        match values.as_slice() {
+            [radius, sigma, amount, threshold] => {
+                Ok(UnsharpMask::new(*radius, *sigma, *amount, *threshold))
+            }
+            _ => bail!("Invalid unsharp mask \"{s}\""),
+        }
+    }
+}
+
+/// Shadow added under the non-transparent pixels of the image
+/// (`e-shadow`). Fields left unset use the ImageKit defaults.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/image-enhancement-and-color-manipulation#shadow-e-shadow
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Shadow {
+    blur: Option<u32>,
+    saturation: Option<u32>,
+    offset_x: Option<i32>,
+    offset_y: Option<i32>,
+}
+
+impl Shadow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the blur radius of the shadow, between `0` and `15`
+    pub fn blur(mut self, val: u32) -> Self {
+        self.blur = Some(val);
+        self
+    }
+
+    /// Sets the saturation of the shadow, between `0` and `100`
+    pub fn saturation(mut self, val: u32) -> Self {
+        self.saturation = Some(val);
+        self
+    }
+
+    /// Sets the horizontal offset as a percentage of the image width
+    pub fn offset_x(mut self, val: i32) -> Self {
+        self.offset_x = Some(val);
+        self
+    }
+
+    /// Sets the vertical offset as a percentage of the image height
+    pub fn offset_y(mut self, val: i32) -> Self {
+        self.offset_y = Some(val);
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if matches!(self.blur, Some(blur) if blur > 15) {
+            bail!("Shadow blur must be between 0 and 15");
+        }
+
+        if matches!(self.saturation, Some(saturation) if saturation > 100) {
+            bail!("Shadow saturation must be between 0 and 100");
+        }
+
+        for offset in [self.offset_x, self.offset_y].into_iter().flatten() {
+            if !(-100..=100).contains(&offset) {
+                bail!("Shadow offsets must be between -100 and 100");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Shadow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut params: Vec<String> = Vec::new();
+
+        if let Some(blur) = self.blur {
+            params.push(format!("bl-{blur}"));
+        }
+
+        if let Some(saturation) = self.saturation {
+            params.push(format!("st-{saturation}"));
+        }
+
+        if let Some(offset_x) = self.offset_x {
+            params.push(format!("x-{}", signed(offset_x)));
+        }
+
+        if let Some(offset_y) = self.offset_y {
+            params.push(format!("y-{}", signed(offset_y)));
+        }
+
+        if params.is_empty() {
+            write!(f, "shadow")
+        } else {
+            write!(f, "shadow-{}", params.join("_"))
+        }
+    }
+}
+
+impl FromStr for Shadow {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut shadow = Shadow::new();
+
+        for (key, value) in effect_params(s, "shadow")? {
+            match key {
+                "bl" => shadow.blur = Some(value.parse()?),
+                "st" => shadow.saturation = Some(value.parse()?),
+                "x" => shadow.offset_x = Some(parse_signed(value)?),
+                "y" => shadow.offset_y = Some(parse_signed(value)?),
+                _ => bail!("Invalid shadow \"{s}\""),
+            }
+        }
+
+        Ok(shadow)
+    }
+}
+
+/// Linear gradient overlaid on the image (`e-gradient`). Fields left unset
+/// use the ImageKit defaults.
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/image-enhancement-and-color-manipulation#gradient-e-gradient
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gradient {
+    direction: Option<u32>,
+    from: Option<String>,
+    to: Option<String>,
+    stop_point: Option<f32>,
+}
+
+impl Gradient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the direction of the gradient in degrees
+    pub fn direction(mut self, val: u32) -> Self {
+        self.direction = Some(val);
+        self
+    }
+
+    /// Sets the start color of the gradient
+    pub fn from<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.from = Some(val.into());
+        self
+    }
+
+    /// Sets the end color of the gradient
+    pub fn to<T: AsRef<str> + Into<String>>(mut self, val: T) -> Self {
+        self.to = Some(val.into());
+        self
+    }
+
+    /// Sets the position where the end color starts, between `0` and `1`
+    pub fn stop_point(mut self, val: f32) -> Self {
+        self.stop_point = Some(val);
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if matches!(self.direction, Some(direction) if direction >= 360) {
+            bail!("Gradient direction must be between 0 and 359");
+        }
+
+        for color in [&self.from, &self.to].into_iter().flatten() {
+            validate_color(color)?;
+        }
+
+        if matches!(self.stop_point, Some(stop_point) if !(0.0..=1.0).contains(&stop_point)) {
+            bail!("Gradient stop point must be between 0 and 1");
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Gradient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut params: Vec<String> = Vec::new();
+
+        if let Some(direction) = self.direction {
+            params.push(format!("ld-{direction}"));
+        }
+
+        if let Some(from) = &self.from {
+            params.push(format!("from-{from}"));
+        }
+
+        if let Some(to) = &self.to {
+            params.push(format!("to-{to}"));
+        }
+
+        if let Some(stop_point) = self.stop_point {
+            params.push(format!("sp-{stop_point}"));
+        }
+
+        if params.is_empty() {
+            write!(f, "gradient")
+        } else {
+            write!(f, "gradient-{}", params.join("_"))
+        }
+    }
+}
+
+impl FromStr for Gradient {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut gradient = Gradient::new();
+
+        for (key, value) in effect_params(s, "gradient")? {
+            match key {
+                "ld" => gradient.direction = Some(value.parse()?),
+                "from" => gradient.from = Some(value.to_string()),
+                "to" => gradient.to = Some(value.to_string()),
+                "sp" => gradient.stop_point = Some(value.parse()?),
+                _ => bail!("Invalid gradient \"{s}\""),
+            }
+        }
+
+        Ok(gradient)
+    }
+}
+
+/// Border added around the image (`b-<width>_<color>`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#border-b
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Border {
+    pub width: u32,
+    pub color: String,
+}
+
+impl Border {
+    pub fn new<T: AsRef<str> + Into<String>>(width: u32, color: T) -> Self {
+        Self {
+            width,
+            color: color.into(),
+        }
+    }
+}
+
+impl Display for Border {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.width, self.color)
+    }
+}
+
+impl FromStr for Border {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((width, color)) = s.split_once('_') else {
+            bail!("Invalid border \"{s}\"");
+        };
+
+        Ok(Border::new(width.parse()?, color))
+    }
+}
+
+/// Corner radius of the image (`r-`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#radius-r
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Radius {
+    Pixels(u32),
+    /// `r-max`, makes the image a circle or an ellipse
+    Max,
+}
+
+impl Display for Radius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Radius::Pixels(val) => write!(f, "{val}"),
+            Radius::Max => write!(f, "max"),
+        }
+    }
+}
+
+impl FromStr for Radius {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "max" => Ok(Radius::Max),
+            val => Ok(Radius::Pixels(val.parse()?)),
+        }
+    }
+}
+
+/// Rotation applied to the image (`rt-`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#rotate-rt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// Clockwise rotation in degrees, negative values rotate
+    /// counter-clockwise
+    Degrees(i32),
+    /// `rt-auto`, rotates based on the EXIF orientation
+    Auto,
+}
+
+impl Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rotation::Degrees(val) => write!(f, "{}", signed(*val)),
+            Rotation::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+impl FromStr for Rotation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(Rotation::Auto),
+            val => Ok(Rotation::Degrees(parse_signed(val)?)),
+        }
+    }
+}
+
+/// Flips the image (`fl-`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#flip-fl
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flip {
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl Display for Flip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Flip::Horizontal => write!(f, "h"),
+            Flip::Vertical => write!(f, "v"),
+            Flip::Both => write!(f, "h_v"),
+        }
+    }
+}
+
+impl FromStr for Flip {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "h" => Ok(Flip::Horizontal),
+            "v" => Ok(Flip::Vertical),
+            "h_v" | "v_h" => Ok(Flip::Both),
+            _ => bail!("Invalid flip \"{s}\""),
+        }
+    }
+}
+
+/// Trims solid borders around the image (`t-`).
+///
+/// Refer: https://docs.imagekit.io/features/image-transformations/resize-crop-and-other-transformations#trim-edges-t
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trim {
+    /// `t-true`, uses the default threshold
+    Default,
+    /// Threshold between `1` and `99`
+    Threshold(u8),
+}
+
+impl Display for Trim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trim::Default => write!(f, "true"),
+            Trim::Threshold(val) => write!(f, "{val}"),
+        }
+    }
+}
+
+impl FromStr for Trim {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "true" => Ok(Trim::Default),
+            val => Ok(Trim::Threshold(val.parse()?)),
+        }
+    }
+}
+
+/// Formats a signed number as expected by ImageKit, where negative values
+/// are prefixed with `N` instead of `-`.
+pub(crate) fn signed(val: i32) -> String {
+    if val < 0 {
+        format!("N{}", val.unsigned_abs())
+    } else {
+        val.to_string()
+    }
+}
+
+/// Parses a signed number in the format used by ImageKit, where negative
+/// values are prefixed with `N` instead of `-`.
+pub(crate) fn parse_signed(val: &str) -> Result<i32> {
+    match val.strip_prefix('N') {
+        Some(val) => Ok(-val.parse::<i32>()?),
+        None => Ok(val.parse()?),
+    }
+}
+
+/// Splits effects with optional arguments such as `shadow-bl-15_st-40` into
+/// their `(key, value)` pairs.
+pub(crate) fn effect_params<'a>(s: &'a str, effect: &str) -> Result<Vec<(&'a str, &'a str)>> {
+    let Some(params) = s.strip_prefix(effect) else {
+        bail!("Invalid {effect} \"{s}\"");
+    };
+
+    if params.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let Some(params) = params.strip_prefix('-') else {
+        bail!("Invalid {effect} \"{s}\"");
+    };
+
+    params
+        .split('_')
+        .map(|param| match param.split_once('-') {
+            Some(pair) => Ok(pair),
+            None => bail!("Invalid {effect} \"{s}\""),
+        })
+        .collect()
+}
+
+/// Validates colors provided either as hexadecimal codes (`FF0000`,
+/// `FF000080`) or as color names (`red`).
+pub(crate) fn validate_color(color: &str) -> Result<()> {
+    if color.is_empty() || !color.chars().all(|c| c.is_ascii_alphanumeric()) {
+        bail!("Invalid color \"{color}\"");
+    }
+
+    Ok(())
+}
+
+/// Encodes free text as Base64 for the parameters that support it (`ie-`,
+/// `prompte-`), escaping the characters not allowed in a transformation.
+pub(crate) fn encode_base64(val: &str) -> String {
+    STANDARD
+        .encode(val)
+        .replace('+', "%2B")
+        .replace('/', "%2F")
+        .replace('=', "%3D")
+}
+
+/// Decodes text encoded with `encode_base64`, either as it is found in the
+/// URL or after the query string was decoded.
+pub(crate) fn decode_base64(val: &str) -> Result<String> {
+    let val = val
+        .replace("%2B", "+")
+        .replace("%2F", "/")
+        .replace("%3D", "=");
+
+    Ok(String::from_utf8(STANDARD.decode(val)?)?)
+}
@@ -0,0 +1,101 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+
+/// Video codec of the output video (`vc-`).
+///
+/// Refer: https://docs.imagekit.io/features/video-transformation/video-optimization#video-codec-vc
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoCodec {
+    H264,
+    Vp9,
+    Av1,
+    /// `vc-none`, removes the video track
+    None,
+}
+
+impl Display for VideoCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VideoCodec::H264 => write!(f, "h264"),
+            VideoCodec::Vp9 => write!(f, "vp9"),
+            VideoCodec::Av1 => write!(f, "av1"),
+            VideoCodec::None => write!(f, "none"),
+        }
+    }
+}
+
+impl FromStr for VideoCodec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "h264" => Ok(VideoCodec::H264),
+            "vp9" => Ok(VideoCodec::Vp9),
+            "av1" => Ok(VideoCodec::Av1),
+            "none" => Ok(VideoCodec::None),
+            _ => bail!("Invalid video codec \"{s}\""),
+        }
+    }
+}
+
+/// Audio codec of the output video (`ac-`).
+///
+/// Refer: https://docs.imagekit.io/features/video-transformation/video-optimization#audio-codec-ac
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioCodec {
+    Aac,
+    Opus,
+    /// `ac-none`, removes the audio track
+    None,
+}
+
+impl Display for AudioCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioCodec::Aac => write!(f, "aac"),
+            AudioCodec::Opus => write!(f, "opus"),
+            AudioCodec::None => write!(f, "none"),
+        }
+    }
+}
+
+impl FromStr for AudioCodec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "aac" => Ok(AudioCodec::Aac),
+            "opus" => Ok(AudioCodec::Opus),
+            "none" => Ok(AudioCodec::None),
+            _ => bail!("Invalid audio codec \"{s}\""),
+        }
+    }
+}
+
+/// Adaptive bitrate streaming protocol.
+///
+/// Refer: https://docs.imagekit.io/features/video-transformation/adaptive-bitrate-streaming
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    /// HTTP Live Streaming, served from `ik-master.m3u8`
+    Hls,
+    /// MPEG-DASH, served from `ik-master.mpd`
+    Dash,
+}
+
+impl Protocol {
+    /// Name of the manifest file appended to the video path
+    pub fn manifest(&self) -> &'static str {
+        match self {
+            Protocol::Hls => "ik-master.m3u8",
+            Protocol::Dash => "ik-master.mpd",
+        }
+    }
+}
+
+/// Name of the file appended to the video path to retrieve a thumbnail.
+///
+/// Refer: https://docs.imagekit.io/features/video-transformation/get-thumbnail-from-a-video
+pub const THUMBNAIL: &str = "ik-thumbnail.jpg";
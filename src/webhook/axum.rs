@@ -0,0 +1,107 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ::axum::body::Bytes;
+use ::axum::extract::State;
+use ::axum::http::{HeaderMap, StatusCode};
+use ::axum::routing::post;
+use ::axum::Router;
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{verify_signature, WebhookEvent, SIGNATURE_HEADER};
+
+/// Receives the webhook events verified by the `router`.
+///
+/// Implemented for async closures taking a `WebhookEvent`, e.g.
+/// `|event| async move { Ok(()) }`.
+#[async_trait]
+pub trait WebhookHandler: Send + Sync + 'static {
+    /// Handles a verified event, errors are answered with
+    /// `500 Internal Server Error` so ImageKit retries the delivery
+    async fn handle(&self, event: WebhookEvent) -> Result<()>;
+}
+
+#[async_trait]
+impl<F, Fut> WebhookHandler for F
+where
+    F: Fn(WebhookEvent) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send,
+{
+    async fn handle(&self, event: WebhookEvent) -> Result<()> {
+        self(event).await
+    }
+}
+
+struct Receiver<H> {
+    secret: String,
+    tolerance: Duration,
+    handler: H,
+}
+
+/// Creates a `Router` accepting ImageKit webhooks on `POST /`, to be nested
+/// at the path configured in the ImageKit dashboard:
+///
+/// ```ignore
+/// let app = Router::new().nest(
+///     "/webhooks/imagekit",
+///     webhook::axum::router(secret, DEFAULT_TOLERANCE, |event| async move {
+///         println!("{event:?}");
+///         Ok(())
+///     }),
+/// );
+/// ```
+///
+/// Requests are answered with:
+///
+/// - `401 Unauthorized` when the signature is missing, invalid or outside of
+///   the `tolerance` window
+/// - `400 Bad Request` when the body is not a valid event
+/// - `500 Internal Server Error` when the handler fails, its error is logged
+///   with `tracing::error!`
+/// - `200 OK` otherwise
+pub fn router<S, H>(secret: S, tolerance: Duration, handler: H) -> Router
+where
+    S: Into<String>,
+    H: WebhookHandler,
+{
+    let receiver = Arc::new(Receiver {
+        secret: secret.into(),
+        tolerance,
+        handler,
+    });
+
+    Router::new()
+        .route("/", post(receive::<H>))
+        .with_state(receiver)
+}
+
+async fn receive<H: WebhookHandler>(
+    State(receiver): State<Arc<Receiver<H>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let Some(signature) = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return StatusCode::UNAUTHORIZED;
+    };
+
+    if verify_signature(&body, signature, &receiver.secret, receiver.tolerance).is_err() {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let Ok(event) = serde_json::from_slice::<WebhookEvent>(&body) else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    match receiver.handler.handle(event).await {
+        Ok(()) => StatusCode::OK,
+        Err(err) => {
+            tracing::error!(error = %format_args!("{err:#}"), "ImageKit webhook handler failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
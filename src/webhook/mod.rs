@@ -0,0 +1,141 @@
+#[cfg(all(feature = "webhooks-axum", not(target_arch = "wasm32")))]
+pub mod axum;
+pub mod types;
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub use self::types::WebhookEvent;
+
+/// Header containing the signature of the webhook request
+pub const SIGNATURE_HEADER: &str = "x-ik-signature";
+
+/// Recommended maximum difference between the webhook timestamp and the
+/// current time
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(300);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Verifies the signature of a webhook request and deserializes its body.
+///
+/// `raw_body` must be the body exactly as received, `signature_header` the
+/// value of the `x-ik-signature` header and `secret` the webhook secret from
+/// the ImageKit dashboard. Requests with a timestamp further than
+/// `tolerance` from the current time are rejected to prevent replays.
+///
+/// Not available on `wasm32` targets which have no clock, use `verify_at`.
+///
+/// Refer: https://docs.imagekit.io/extensions/overview/webhooks#verify-webhook-signature
+#[cfg(not(target_arch = "wasm32"))]
+pub fn verify<B: AsRef<[u8]>>(
+    raw_body: B,
+    signature_header: &str,
+    secret: &str,
+    tolerance: Duration,
+) -> Result<WebhookEvent> {
+    verify_at(raw_body, signature_header, secret, tolerance, now()?)
+}
+
+/// Verifies the signature of a webhook request as `verify` does, at `now`
+/// in milliseconds since the Unix epoch
+pub fn verify_at<B: AsRef<[u8]>>(
+    raw_body: B,
+    signature_header: &str,
+    secret: &str,
+    tolerance: Duration,
+    now: u64,
+) -> Result<WebhookEvent> {
+    verify_signature_at(raw_body.as_ref(), signature_header, secret, tolerance, now)?;
+
+    Ok(serde_json::from_slice(raw_body.as_ref())?)
+}
+
+/// Verifies the signature of a webhook request without deserializing its
+/// body, the signatures are compared in constant time
+#[cfg(not(target_arch = "wasm32"))]
+pub fn verify_signature<B: AsRef<[u8]>>(
+    raw_body: B,
+    signature_header: &str,
+    secret: &str,
+    tolerance: Duration,
+) -> Result<()> {
+    verify_signature_at(raw_body, signature_header, secret, tolerance, now()?)
+}
+
+/// Verifies the signature of a webhook request as `verify_signature` does,
+/// at `now` in milliseconds since the Unix epoch
+pub fn verify_signature_at<B: AsRef<[u8]>>(
+    raw_body: B,
+    signature_header: &str,
+    secret: &str,
+    tolerance: Duration,
+    now: u64,
+) -> Result<()> {
+    let signature = Signature::parse(signature_header)?;
+
+    if now.abs_diff(signature.timestamp) > tolerance.as_millis() as u64 {
+        bail!("Webhook timestamp is outside of the tolerance");
+    }
+
+    mac(raw_body.as_ref(), secret, signature.timestamp)?
+        .verify_slice(&signature.v1)
+        .map_err(|_| anyhow!("Invalid webhook signature"))
+}
+
+/// Current time in milliseconds since the Unix epoch
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> Result<u64> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64)
+}
+
+/// Signs `raw_body` as ImageKit does, returning the value of the
+/// `x-ik-signature` header. `timestamp` is in milliseconds since the Unix
+/// epoch. Useful to test webhook handlers.
+pub fn sign<B: AsRef<[u8]>>(raw_body: B, secret: &str, timestamp: u64) -> Result<String> {
+    let signature = mac(raw_body.as_ref(), secret, timestamp)?
+        .finalize()
+        .into_bytes();
+
+    Ok(format!("t={timestamp},v1={}", hex::encode(signature)))
+}
+
+/// HMAC-SHA256 of `<timestamp>.<raw_body>` keyed with the webhook secret
+fn mac(raw_body: &[u8], secret: &str, timestamp: u64) -> Result<HmacSha256> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())?;
+
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(raw_body);
+
+    Ok(mac)
+}
+
+/// Value of the `x-ik-signature` header, i.e. `t=<timestamp>,v1=<signature>`
+struct Signature {
+    timestamp: u64,
+    v1: Vec<u8>,
+}
+
+impl Signature {
+    fn parse(header: &str) -> Result<Self> {
+        let mut timestamp = None;
+        let mut v1 = None;
+
+        for item in header.split(',') {
+            match item.trim().split_once('=') {
+                Some(("t", value)) => timestamp = Some(value.parse()?),
+                Some(("v1", value)) => v1 = Some(hex::decode(value)?),
+                _ => {}
+            }
+        }
+
+        match (timestamp, v1) {
+            (Some(timestamp), Some(v1)) => Ok(Signature { timestamp, v1 }),
+            _ => bail!("Invalid webhook signature header \"{header}\""),
+        }
+    }
+}
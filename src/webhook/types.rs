@@ -0,0 +1,154 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::upload::types::Response as UploadResponse;
+
+/// Event sent by ImageKit to the webhook endpoint, identified by its `type`.
+///
+/// Refer: https://docs.imagekit.io/extensions/overview/webhooks
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum WebhookEvent {
+    /// The video transformation request was accepted for processing
+    #[serde(rename = "video.transformation.accepted")]
+    VideoTransformationAccepted(Event<VideoTransformation>),
+    /// The transformed video is ready to be served
+    #[serde(rename = "video.transformation.ready")]
+    VideoTransformationReady(Event<VideoTransformation>),
+    /// The video transformation failed
+    #[serde(rename = "video.transformation.error")]
+    VideoTransformationError(Event<VideoTransformation>),
+    /// The transformation applied before storing the uploaded file
+    /// succeeded
+    #[serde(rename = "upload.pre-transform.success")]
+    UploadPreTransformSuccess(Event<UploadResponse>),
+    /// The transformation applied before storing the uploaded file failed,
+    /// the file was not stored
+    #[serde(rename = "upload.pre-transform.error")]
+    UploadPreTransformError(Event<UploadTransformError>),
+    /// A transformation requested on upload was generated
+    #[serde(rename = "upload.post-transform.success")]
+    UploadPostTransformSuccess(Event<UploadPostTransform>),
+    /// A transformation requested on upload failed
+    #[serde(rename = "upload.post-transform.error")]
+    UploadPostTransformError(Event<UploadTransformError>),
+    /// Any other event, e.g. extension events
+    #[serde(other)]
+    Unknown,
+}
+
+/// Fields shared by every webhook event.
+#[derive(Debug, Deserialize)]
+pub struct Event<T> {
+    /// Unique identifier of the event
+    pub id: String,
+    /// Timestamp of the event in ISO 8601 format
+    pub created_at: String,
+    /// Information about the request which triggered the event
+    pub request: EventRequest,
+    pub data: T,
+}
+
+/// Request which triggered a webhook event.
+#[derive(Debug, Deserialize)]
+pub struct EventRequest {
+    /// Unique identifier of the originating request
+    pub x_request_id: String,
+    /// URL of the originating request, only for video transformations
+    pub url: Option<String>,
+    /// User agent of the originating request, only for video
+    /// transformations
+    pub user_agent: Option<String>,
+    /// Transformation requested on upload, only for upload events
+    pub transformation: Option<Value>,
+}
+
+/// Data of the `video.transformation.*` events.
+#[derive(Debug, Deserialize)]
+pub struct VideoTransformation {
+    pub asset: Asset,
+    pub transformation: VideoTransformationDetails,
+    /// Time spent processing the video, only for
+    /// `video.transformation.ready`
+    pub timings: Option<Timings>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Asset {
+    /// URL of the source video
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VideoTransformationDetails {
+    /// Type of the output, i.e. `video-transformation`, `gif-to-video` or
+    /// `video-thumbnail`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Transformation options, e.g. format, codecs or resolutions
+    pub options: Option<Value>,
+    /// Transformed video, only for `video.transformation.ready`
+    pub output: Option<VideoOutput>,
+    /// Reason of the failure, only for `video.transformation.error`
+    pub error: Option<TransformationError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VideoOutput {
+    /// URL of the transformed video
+    pub url: String,
+    pub video_metadata: Option<VideoMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VideoMetadata {
+    /// Duration of the video in seconds
+    pub duration: f64,
+    pub width: u64,
+    pub height: u64,
+    /// Bitrate of the video in bits per second
+    pub bitrate: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Timings {
+    /// Time spent downloading the source video in milliseconds
+    pub download_duration: Option<u64>,
+    /// Time spent encoding the video in milliseconds
+    pub encoding_duration: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransformationError {
+    /// Reason of the failure, e.g. `encoding_failed` or `download_failed`
+    pub reason: String,
+}
+
+/// Data of the `upload.post-transform.success` event.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadPostTransform {
+    pub file_id: String,
+    pub name: String,
+    /// URL of the transformed file
+    pub url: String,
+}
+
+/// Data of the `upload.pre-transform.error` and
+/// `upload.post-transform.error` events.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadTransformError {
+    /// Only for `upload.post-transform.error`, as the file is not stored
+    /// when the pre-transformation fails
+    pub file_id: Option<String>,
+    pub name: String,
+    pub path: String,
+    pub url: Option<String>,
+    pub transformation: UploadTransformationFailure,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadTransformationFailure {
+    pub error: TransformationError,
+}